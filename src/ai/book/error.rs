@@ -0,0 +1,17 @@
+use crate::sgf::SgfError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BookError {
+    #[error("Not an opening book file")]
+    BadMagic,
+
+    #[error("Unsupported opening book version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Failed to read book game: {0}")]
+    Sgf(#[from] SgfError),
+
+    #[error("Failed to access opening book: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,317 @@
+pub mod error;
+
+pub use error::BookError;
+
+use crate::ai::GoAI;
+use crate::ai::tuning::{MatchSettings, play_game};
+use crate::core::game::Move;
+use crate::core::seed;
+use crate::core::{Board, Position, Stone, Symmetry};
+use crate::sgf::{GameTree, sgf_files};
+use rand::Rng;
+use rand::rngs::StdRng;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+const MAGIC: &[u8; 4] = b"BGOB";
+const VERSION: u32 = 1;
+const WHITE_TO_PLAY: u64 = 0x626F_6F6B_2D77_6869;
+
+/// Statistics of one move from a book position, in the position's canonical
+/// orientation. `wins` counts games won by the side that played it; unknown
+/// results count as half a win.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookMove {
+    pub pos: Position,
+    pub games: u32,
+    pub wins: f32,
+}
+
+impl BookMove {
+    /// Win rate with one virtual win and one virtual loss.
+    pub fn win_rate(&self) -> f32 {
+        (self.wins + 1.0) / (self.games as f32 + 2.0)
+    }
+}
+
+/// Opening moves keyed by the symmetry-normalised Zobrist hash of the
+/// position and the side to move, so that rotated and mirrored openings
+/// share their statistics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpeningBook {
+    positions: HashMap<u64, Vec<BookMove>>,
+    /// Moves recorded per game.
+    pub depth: usize,
+}
+
+impl OpeningBook {
+    pub fn new(depth: usize) -> Self {
+        OpeningBook {
+            positions: HashMap::new(),
+            depth,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Book moves for `board` with `player` to move, in the board's own
+    /// orientation.
+    pub fn moves(&self, board: &Board, player: Stone) -> Vec<BookMove> {
+        let (key, symmetries) = canonical_key(board, player);
+        let (size, symmetry) = (board.size(), symmetries[0]);
+        self.positions
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .map(|&mv| BookMove {
+                pos: mv.pos.transform(symmetry.inverse(), size),
+                ..mv
+            })
+            .collect()
+    }
+
+    /// Records `mv` played by `player` from `board` in a game `winner` won.
+    pub fn add_move(&mut self, board: &Board, player: Stone, mv: Position, winner: Option<Stone>) {
+        let (key, symmetries) = canonical_key(board, player);
+        // Among equivalent orientations of a symmetric position, the move
+        // with the smallest coordinates stands for all of them.
+        let pos = symmetries
+            .iter()
+            .map(|&symmetry| mv.transform(symmetry, board.size()))
+            .min_by_key(|p| (p.y, p.x))
+            .unwrap_or(mv);
+        let win = match winner {
+            Some(stone) if stone == player => 1.0,
+            Some(_) => 0.0,
+            None => 0.5,
+        };
+
+        let moves = self.positions.entry(key).or_default();
+        match moves.iter_mut().find(|m| m.pos == pos) {
+            Some(entry) => {
+                entry.games += 1;
+                entry.wins += win;
+            }
+            None => moves.push(BookMove {
+                pos,
+                games: 1,
+                wins: win,
+            }),
+        }
+    }
+
+    /// Adds the first `depth` moves of the main line. The winner is read from
+    /// the `RE` property. Nothing is added when the record has an illegal move.
+    pub fn add_game(&mut self, tree: &GameTree) -> Result<(), BookError> {
+        let winner = tree.winner();
+        let mut moves = Vec::new();
+        tree.replay_main_line(|board, mv| {
+            if moves.len() < self.depth {
+                moves.push((board.clone(), mv));
+            }
+        })?;
+
+        for (board, mv) in moves {
+            if let Move::Play(stone, pos) = mv {
+                self.add_move(&board, stone, pos, winner);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds every `.sgf` game in `dir`, skipping unreadable or illegal
+    /// records. Returns `(games added, games skipped)`.
+    pub fn add_directory(&mut self, dir: impl AsRef<Path>) -> Result<(usize, usize), BookError> {
+        let (mut added, mut skipped) = (0, 0);
+        for path in sgf_files(dir)? {
+            match GameTree::load(&path) {
+                Ok(tree) if self.add_game(&tree).is_ok() => added += 1,
+                _ => skipped += 1,
+            }
+        }
+        Ok((added, skipped))
+    }
+
+    /// Plays `games` games between `black` and `white` and adds them, scored
+    /// by area with no dead stones.
+    pub fn add_self_play(
+        &mut self,
+        black: &mut dyn GoAI,
+        white: &mut dyn GoAI,
+        games: usize,
+        settings: &MatchSettings,
+        rng: &mut impl Rng,
+    ) -> Result<(), BookError> {
+        for _ in 0..games {
+            let game = play_game(black, white, settings, rng);
+            let mut tree = GameTree::from_game(&game);
+            tree.result = Some(match game.score(&[]).winner() {
+                Stone::Black => "B+".to_string(),
+                Stone::White => "W+".to_string(),
+            });
+            self.add_game(&tree)?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BookError> {
+        let mut entries: Vec<_> = self.positions.iter().collect();
+        entries.sort_by_key(|&(&key, _)| key);
+
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&(self.depth as u32).to_le_bytes())?;
+        out.write_all(&(entries.len() as u32).to_le_bytes())?;
+        for (key, moves) in entries {
+            out.write_all(&key.to_le_bytes())?;
+            out.write_all(&(moves.len() as u16).to_le_bytes())?;
+            for mv in moves {
+                out.write_all(&[mv.pos.x as u8, mv.pos.y as u8])?;
+                out.write_all(&mv.games.to_le_bytes())?;
+                out.write_all(&mv.wins.to_le_bytes())?;
+            }
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BookError> {
+        let mut input = std::io::BufReader::new(std::fs::File::open(path)?);
+
+        let mut magic = [0; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(BookError::BadMagic);
+        }
+        let version = u32::from_le_bytes(read_array(&mut input)?);
+        if version != VERSION {
+            return Err(BookError::UnsupportedVersion(version));
+        }
+
+        let mut book = OpeningBook::new(u32::from_le_bytes(read_array(&mut input)?) as usize);
+        let count = u32::from_le_bytes(read_array(&mut input)?);
+        for _ in 0..count {
+            let key = u64::from_le_bytes(read_array(&mut input)?);
+            let len = u16::from_le_bytes(read_array(&mut input)?);
+            let moves = (0..len)
+                .map(|_| {
+                    let [x, y] = read_array(&mut input)?;
+                    Ok(BookMove {
+                        pos: Position {
+                            x: x as usize,
+                            y: y as usize,
+                        },
+                        games: u32::from_le_bytes(read_array(&mut input)?),
+                        wins: f32::from_le_bytes(read_array(&mut input)?),
+                    })
+                })
+                .collect::<std::io::Result<_>>()?;
+            book.positions.insert(key, moves);
+        }
+        Ok(book)
+    }
+}
+
+/// Plays book moves while the position is in the book and hands over to
+/// `engine` afterwards. Book moves are drawn with probability proportional
+/// to games played times smoothed win rate.
+pub struct BookAI<A> {
+    pub book: Arc<OpeningBook>,
+    pub engine: A,
+    /// Moves seen in fewer games are ignored.
+    pub min_games: u32,
+    rng: StdRng,
+}
+
+impl<A: GoAI> BookAI<A> {
+    pub fn new(book: Arc<OpeningBook>, engine: A, seed: u64) -> Self {
+        BookAI {
+            book,
+            engine,
+            min_games: 1,
+            rng: seed::rng(seed),
+        }
+    }
+
+    fn book_move(&mut self, board: &Board, player: Stone) -> Option<Position> {
+        let moves: Vec<(Position, f32)> = self
+            .book
+            .moves(board, player)
+            .into_iter()
+            .filter(|mv| mv.games >= self.min_games && board.is_valid_move(mv.pos, player))
+            .map(|mv| (mv.pos, mv.games as f32 * mv.win_rate()))
+            .collect();
+
+        let total: f32 = moves.iter().map(|&(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut target = self.rng.random_range(0.0..total);
+        for &(pos, weight) in &moves {
+            if target < weight {
+                return Some(pos);
+            }
+            target -= weight;
+        }
+        moves.last().map(|&(pos, _)| pos)
+    }
+}
+
+impl<A: GoAI> GoAI for BookAI<A> {
+    fn select_move(&mut self, board: &Board, player: Stone) -> Option<Position> {
+        self.engine.stop_pondering();
+        self.book_move(board, player)
+            .or_else(|| self.engine.select_move(board, player))
+    }
+
+    fn move_probabilities(&self, board: &Board, player: Stone) -> Option<Vec<f32>> {
+        self.engine.move_probabilities(board, player)
+    }
+
+    fn ponder(&mut self, board: &Board, player: Stone) {
+        self.engine.ponder(board, player);
+    }
+
+    fn stop_pondering(&mut self) {
+        self.engine.stop_pondering();
+    }
+
+    fn is_pondering(&self) -> bool {
+        self.engine.is_pondering()
+    }
+}
+
+/// Smallest hash over the eight symmetries, and every symmetry that gives
+/// it; symmetric positions have several. The hash also covers the board size
+/// and the side to move.
+fn canonical_key(board: &Board, player: Stone) -> (u64, Vec<Symmetry>) {
+    let mut extra = seed::mix(board.size() as u64);
+    if player == Stone::White {
+        extra ^= seed::mix(WHITE_TO_PLAY);
+    }
+
+    let hashes = board.symmetric_hashes();
+    let key = hashes.iter().min().copied().unwrap_or_default();
+    let symmetries = Symmetry::ALL
+        .into_iter()
+        .zip(hashes)
+        .filter(|&(_, hash)| hash == key)
+        .map(|(symmetry, _)| symmetry)
+        .collect();
+    (key ^ extra, symmetries)
+}
+
+fn read_array<const N: usize>(input: &mut impl Read) -> std::io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
@@ -0,0 +1,142 @@
+use crate::core::board::MOVE_HISTORY;
+use crate::core::{Board, Position, Stone};
+use crate::tactics::ladder;
+
+pub const OWN_STONES: usize = 0;
+pub const OPPONENT_STONES: usize = 1;
+pub const EMPTY: usize = 2;
+/// Four planes: stones whose group has 1, 2, 3, and 4 or more liberties.
+pub const LIBERTIES: usize = 3;
+/// Empty points where the side to move would leave its own group in atari.
+pub const SELF_ATARI: usize = 7;
+/// Empty points where the side to move would put opponent stones in atari.
+pub const ATARI: usize = 8;
+/// The point the side to move may not retake because of ko.
+pub const KO: usize = 9;
+pub const LEGAL: usize = 10;
+/// Moves that start a working ladder against an opponent group.
+pub const LADDER_CAPTURE: usize = 11;
+/// Moves that run a group in atari out of a ladder.
+pub const LADDER_ESCAPE: usize = 12;
+/// `MOVE_HISTORY` planes holding one recent move each, the latest first.
+pub const HISTORY: usize = 13;
+/// All ones when Black is to move.
+pub const BLACK_TO_PLAY: usize = HISTORY + MOVE_HISTORY;
+/// All ones, so that padded convolutions can tell where the board ends.
+pub const ONES: usize = BLACK_TO_PLAY + 1;
+pub const PLANES: usize = ONES + 1;
+
+/// Stacks the input planes for `board` with `player` to move. Training and
+/// inference both use this encoding. Every plane is a `size * size` block
+/// indexed like `Board::pos_to_index`, seen from the side to move.
+pub fn encode(board: &Board, player: Stone) -> Vec<f32> {
+    let size = board.size();
+    let area = size * size;
+    let opponent = player.opposite();
+    let mut planes = vec![0.0; PLANES * area];
+    let mut set = |plane: usize, index: usize| planes[plane * area + index] = 1.0;
+
+    // Liberties of each stone's group, counted once per group, and the
+    // liberties of groups with at most two: the only points where a move
+    // can give atari, start a ladder or run from one.
+    let mut liberties = vec![0; area];
+    let mut urgent = vec![false; area];
+    for index in 0..area {
+        let pos = Position {
+            x: index % size,
+            y: index / size,
+        };
+        if liberties[index] > 0 || board.get_stone(pos).ok().flatten().is_none() {
+            continue;
+        }
+        let group = board.get_group(pos);
+        let group_liberties = board.liberties(&group);
+        for stone in &group {
+            liberties[board.pos_to_index(*stone)] = group_liberties.len();
+        }
+        if group_liberties.len() <= 2 {
+            for liberty in group_liberties {
+                urgent[board.pos_to_index(liberty)] = true;
+            }
+        }
+    }
+
+    let mut after = board.clone();
+    for index in 0..area {
+        let pos = Position {
+            x: index % size,
+            y: index / size,
+        };
+        set(ONES, index);
+        if player == Stone::Black {
+            set(BLACK_TO_PLAY, index);
+        }
+
+        match board.get_stone(pos).ok().flatten() {
+            Some(stone) => {
+                set(
+                    if stone == player {
+                        OWN_STONES
+                    } else {
+                        OPPONENT_STONES
+                    },
+                    index,
+                );
+                set(LIBERTIES + liberties[index].clamp(1, 4) - 1, index);
+            }
+            None => {
+                set(EMPTY, index);
+                let neighbors = board.get_neighbors(pos);
+                let empty = neighbors
+                    .iter()
+                    .filter(|&&n| board.get_stone(n).ok().flatten().is_none())
+                    .count();
+                // Two empty neighbours make a legal move with two liberties.
+                if empty >= 2 && !urgent[index] {
+                    set(LEGAL, index);
+                    continue;
+                }
+
+                after.clone_from(board);
+                let Ok(outcome) = after.play(pos, player) else {
+                    continue;
+                };
+                set(LEGAL, index);
+                if after.count_liberties(&after.get_group(pos)) == 1 {
+                    set(SELF_ATARI, index);
+                }
+                if outcome.atari {
+                    set(ATARI, index);
+                }
+                if !urgent[index] {
+                    continue;
+                }
+                let captures = neighbors.iter().any(|&n| {
+                    after.get_stone(n).ok().flatten() == Some(opponent)
+                        && after.count_liberties(&after.get_group(n)) == 1
+                        && ladder::is_captured(&after, n, opponent)
+                });
+                if captures {
+                    set(LADDER_CAPTURE, index);
+                }
+                let escapes = neighbors.iter().any(|&n| {
+                    board.get_stone(n).ok().flatten() == Some(player)
+                        && liberties[board.pos_to_index(n)] == 1
+                });
+                if escapes && !ladder::is_captured(&after, pos, opponent) {
+                    set(LADDER_ESCAPE, index);
+                }
+            }
+        }
+    }
+
+    if let Some(ko) = board.ko_point(player) {
+        set(KO, board.pos_to_index(ko));
+    }
+    for (age, mv) in board.recent_moves().iter().enumerate() {
+        if let Some(pos) = mv {
+            set(HISTORY + age, board.pos_to_index(*pos));
+        }
+    }
+    planes
+}
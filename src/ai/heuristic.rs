@@ -1,21 +1,139 @@
-use rand::{random, random_range};
-
 use crate::ai::GoAI;
-use crate::ai::random::RandomAI;
+use crate::ai::patterns::PatternPriors;
+use crate::config::{ConfigError, ConfigFile};
+use crate::tactics::eye::{self, EyeKind};
 use crate::{Board, Position, Stone};
+use std::path::Path;
+use std::sync::Arc;
 
-pub struct HeuristicAI {}
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeuristicAI {
+    pub capture_weight: f64,
+    pub save_weight: f64,
+    pub attack_weight: f64,
+    pub expand_weight: f64,
+    pub eye_weight: f64,
+    /// Learned pattern priors; each move score is multiplied by the move's
+    /// pattern strength raised to `pattern_weight`.
+    pub patterns: Option<Arc<PatternPriors>>,
+    pub pattern_weight: f64,
+}
 
-impl GoAI for HeuristicAI {
-    fn select_move(&self, board: &Board, player: Stone) -> Option<Position> {
+impl Default for HeuristicAI {
+    fn default() -> Self {
+        HeuristicAI {
+            capture_weight: 10.0,
+            save_weight: 2.5,
+            attack_weight: 1.5,
+            expand_weight: 5.0,
+            eye_weight: 1.5,
+            patterns: None,
+            pattern_weight: 1.0,
+        }
+    }
+}
+
+impl HeuristicAI {
+    pub const WEIGHT_NAMES: [&str; 5] = [
+        "capture_weight",
+        "save_weight",
+        "attack_weight",
+        "expand_weight",
+        "eye_weight",
+    ];
+
+    pub fn weights(&self) -> [f64; 5] {
+        [
+            self.capture_weight,
+            self.save_weight,
+            self.attack_weight,
+            self.expand_weight,
+            self.eye_weight,
+        ]
+    }
+
+    pub fn from_weights(weights: [f64; 5]) -> Self {
+        let [
+            capture_weight,
+            save_weight,
+            attack_weight,
+            expand_weight,
+            eye_weight,
+        ] = weights;
+        HeuristicAI {
+            capture_weight,
+            save_weight,
+            attack_weight,
+            expand_weight,
+            eye_weight,
+            ..HeuristicAI::default()
+        }
+    }
+
+    /// Same patterns, different weights.
+    pub fn with_weights(&self, weights: [f64; 5]) -> Self {
+        HeuristicAI {
+            patterns: self.patterns.clone(),
+            pattern_weight: self.pattern_weight,
+            ..Self::from_weights(weights)
+        }
+    }
+
+    pub fn with_patterns(self, patterns: Arc<PatternPriors>, pattern_weight: f64) -> Self {
+        HeuristicAI {
+            patterns: Some(patterns),
+            pattern_weight,
+            ..self
+        }
+    }
+
+    /// Reads `<prefix><name>` for each weight and `<prefix>pattern_weight`,
+    /// keeping the default for missing keys.
+    pub fn from_config(config: &ConfigFile, prefix: &str) -> Result<Self, ConfigError> {
+        let mut weights = HeuristicAI::default().weights();
+        for (weight, name) in weights.iter_mut().zip(Self::WEIGHT_NAMES) {
+            if let Some(value) = config.get(&format!("{prefix}{name}"))? {
+                *weight = value;
+            }
+        }
+        let mut ai = Self::from_weights(weights);
+        // Not one of the tuned weights, as it only matters with patterns.
+        if let Some(value) = config.get(&format!("{prefix}pattern_weight"))? {
+            ai.pattern_weight = value;
+        }
+        Ok(ai)
+    }
+
+    pub fn write_config(&self, config: &mut ConfigFile, prefix: &str) {
+        for (weight, name) in self.weights().into_iter().zip(Self::WEIGHT_NAMES) {
+            config.set(&format!("{prefix}{name}"), weight);
+        }
+        config.set(&format!("{prefix}pattern_weight"), self.pattern_weight);
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_config(&ConfigFile::load(path)?, "")
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let mut config = ConfigFile::default();
+        self.write_config(&mut config, "");
+        config.save(path)
+    }
+
+    fn score_moves(&self, board: &Board, player: Stone) -> Option<Vec<f64>> {
         let opponent = player.opposite();
         let mut scores: Vec<f64> = vec![0.0; board.size() * board.size()];
 
-        let capture_weight = 10.0;
-        let save_weight = 2.5;
-        let attack_weight = 1.5;
-        let expand_weight = 5.0;
-        let eye_weight = 1.5;
+        let HeuristicAI {
+            capture_weight,
+            save_weight,
+            attack_weight,
+            expand_weight,
+            eye_weight,
+            ref patterns,
+            pattern_weight,
+        } = *self;
 
         let mut enemy_count = 0;
         let mut allay_count = 0;
@@ -33,49 +151,50 @@ impl GoAI for HeuristicAI {
                     }
                 }
 
-                if !board.is_valid_move(pos, player) {
+                let eye_kind = eye::classify(board, pos, player);
+                if !board.is_valid_move(pos, player) || eye_kind == EyeKind::Real {
                     scores[idx] = -1.0;
                     continue;
                 }
 
                 let mut capture_score = 0.0;
                 for neighbor in board.get_neighbors(pos) {
-                    if let Ok(Some(stone)) = board.get_stone(neighbor) {
-                        if stone == opponent {
-                            let group = board.get_group(neighbor);
-                            if board.count_liberties(&group) == 1 {
-                                capture_score += capture_weight * group.len() as f64;
-                            }
+                    if let Ok(Some(stone)) = board.get_stone(neighbor)
+                        && stone == opponent
+                    {
+                        let group = board.get_group(neighbor);
+                        if board.count_liberties(&group) == 1 {
+                            capture_score += capture_weight * group.len() as f64;
                         }
                     }
                 }
 
                 let mut save_score = 0.0;
                 for neighbor in board.get_neighbors(pos) {
-                    if let Ok(Some(stone)) = board.get_stone(neighbor) {
-                        if stone == player {
-                            let group = board.get_group(neighbor);
-                            let liberties = board.count_liberties(&group);
-                            if liberties == 1 {
-                                save_score += save_weight * group.len() as f64;
-                            } else if liberties == 2 {
-                                save_score += 0.5 * save_weight * group.len() as f64;
-                            }
+                    if let Ok(Some(stone)) = board.get_stone(neighbor)
+                        && stone == player
+                    {
+                        let group = board.get_group(neighbor);
+                        let liberties = board.count_liberties(&group);
+                        if liberties == 1 {
+                            save_score += save_weight * group.len() as f64;
+                        } else if liberties == 2 {
+                            save_score += 0.5 * save_weight * group.len() as f64;
                         }
                     }
                 }
 
                 let mut attack_score = 0.0;
                 for neighbor in board.get_neighbors(pos) {
-                    if let Ok(Some(stone)) = board.get_stone(neighbor) {
-                        if stone == opponent {
-                            let group = board.get_group(neighbor);
-                            let liberties = board.count_liberties(&group);
-                            if liberties <= 2 {
-                                attack_score += attack_weight
-                                    * (3.0 - liberties as f64)
-                                    * (group.len() as f64).sqrt();
-                            }
+                    if let Ok(Some(stone)) = board.get_stone(neighbor)
+                        && stone == opponent
+                    {
+                        let group = board.get_group(neighbor);
+                        let liberties = board.count_liberties(&group);
+                        if liberties <= 2 {
+                            attack_score += attack_weight
+                                * (3.0 - liberties as f64)
+                                * (group.len() as f64).sqrt();
                         }
                     }
                 }
@@ -89,7 +208,7 @@ impl GoAI for HeuristicAI {
                 expand_score += expand_weight * empty_neighbors as f64;
 
                 let mut eye_score = 0.0;
-                if is_potential_eye(board, pos, player) {
+                if eye_kind == EyeKind::Potential {
                     eye_score += eye_weight;
                 }
 
@@ -98,6 +217,10 @@ impl GoAI for HeuristicAI {
                 if would_be_captured_next_move(board, pos, player) {
                     scores[idx] *= 0.2;
                 }
+
+                if let Some(patterns) = patterns {
+                    scores[idx] *= patterns.strength(board, pos, player).powf(pattern_weight);
+                }
             }
         }
 
@@ -105,14 +228,18 @@ impl GoAI for HeuristicAI {
             return None;
         }
 
+        Some(scores)
+    }
+}
+
+impl GoAI for HeuristicAI {
+    fn select_move(&mut self, board: &Board, player: Stone) -> Option<Position> {
+        let scores = self.score_moves(board, player)?;
+
         let mut max_score = None;
         for (idx, &score) in scores.iter().enumerate() {
             match max_score {
-                None => {
-                    if score > 0.0 {
-                        max_score = Some((idx, score))
-                    }
-                }
+                None if score > 0.0 => max_score = Some((idx, score)),
                 Some((_, current_max)) if score > current_max && score > 0.0 => {
                     max_score = Some((idx, score))
                 }
@@ -127,21 +254,21 @@ impl GoAI for HeuristicAI {
         }
         None
     }
-}
 
-fn is_potential_eye(board: &Board, pos: Position, player: Stone) -> bool {
-    let mut friendly_stones = 0;
-    let mut empty_or_friendly = 0;
-
-    for neighbor in board.get_neighbors(pos) {
-        match board.get_stone(neighbor) {
-            Ok(Some(stone)) if stone == player => friendly_stones += 1,
-            Ok(None) => empty_or_friendly += 1,
-            _ => (),
+    fn move_probabilities(&self, board: &Board, player: Stone) -> Option<Vec<f32>> {
+        let scores = self.score_moves(board, player)?;
+        let total: f64 = scores.iter().filter(|&&s| s > 0.0).sum();
+        if total <= 0.0 {
+            return None;
         }
-    }
 
-    friendly_stones >= 3 || (friendly_stones >= 2 && empty_or_friendly == 4)
+        Some(
+            scores
+                .iter()
+                .map(|&s| (s.max(0.0) / total) as f32)
+                .collect(),
+        )
+    }
 }
 
 fn would_be_captured_next_move(board: &Board, pos: Position, player: Stone) -> bool {
@@ -152,12 +279,12 @@ fn would_be_captured_next_move(board: &Board, pos: Position, player: Stone) -> b
 
     let opponent = player.opposite();
     for neighbor in test_board.get_neighbors(pos) {
-        if let Ok(Some(stone)) = test_board.get_stone(neighbor) {
-            if stone == opponent {
-                let group = test_board.get_group(neighbor);
-                if test_board.count_liberties(&group) == 1 {
-                    return true;
-                }
+        if let Ok(Some(stone)) = test_board.get_stone(neighbor)
+            && stone == opponent
+        {
+            let group = test_board.get_group(neighbor);
+            if test_board.count_liberties(&group) == 1 {
+                return true;
             }
         }
     }
@@ -0,0 +1,1107 @@
+use crate::ai::GoAI;
+use crate::ai::network::Evaluator;
+use crate::ai::ownership::area_owner;
+use crate::ai::patterns::PatternPriors;
+use crate::ai::playout::{self, PlayoutPolicy};
+use crate::ai::transposition::{Edge, Node, Replacement, TranspositionTable};
+use crate::config::{ConfigError, ConfigFile};
+use crate::core::seed;
+use crate::core::{Board, Position, Stone};
+use crate::tactics::eye::{self, EyeKind};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+const WHITE_TO_PLAY: u64 = 0x6D63_7473_2D77_6869;
+const PASSED: u64 = 0x6D63_7473_2D70_6173;
+/// Board size the table is sized for until the first search.
+const DEFAULT_SIZE: usize = 19;
+/// Pondering stops by itself after this many times the playout budget.
+const PONDER_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MctsConfig {
+    pub playouts: usize,
+    /// UCT exploration constant `c` of `c * sqrt(ln N / n)`.
+    pub exploration: f64,
+    pub komi: f32,
+    pub policy: PlayoutPolicy,
+    /// Share nodes between move orders that reach the same position. When
+    /// off, every path gets its own node and the search is a plain tree.
+    pub transpositions: bool,
+    /// Memory budget of the transposition table in megabytes.
+    pub memory_mb: usize,
+    pub replacement: Replacement,
+    /// Blend all-moves-as-first statistics into the UCT value (RAVE).
+    pub rave: bool,
+    /// Visits at which the RAVE and UCT estimates weigh equally: the AMAF
+    /// weight is `sqrt(k / (3n + k))` after `n` visits.
+    pub rave_equivalence: f64,
+    pub threads: usize,
+    pub parallelism: Parallelism,
+    /// Losses added to a path while a thread is playing it out, steering
+    /// other threads elsewhere.
+    pub virtual_loss: u32,
+    /// Keep the table between searches so the subtree of the position after
+    /// the opponent's reply is reused. Needs `transpositions` to find it.
+    pub reuse_tree: bool,
+    /// Keep searching on the opponent's time; needs `reuse_tree`.
+    pub ponder: bool,
+    /// PUCT exploration constant `c` of `c * P * sqrt(N) / (1 + n)`.
+    pub c_puct: f64,
+    pub dirichlet_alpha: f64,
+    /// Share of Dirichlet noise in the root priors; 0 turns it off.
+    pub noise_fraction: f64,
+    /// Move choice temperature; 0 always plays the most visited move.
+    pub temperature: f64,
+    /// Leaves evaluated per network call.
+    pub batch_size: usize,
+}
+
+/// How threads share the work of one search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Parallelism {
+    /// All threads grow one shared graph.
+    #[default]
+    Tree,
+    /// Every thread grows its own graph; root statistics are summed.
+    Root,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            playouts: 1000,
+            exploration: 0.7,
+            komi: 6.5,
+            policy: PlayoutPolicy::light(),
+            transpositions: true,
+            memory_mb: 64,
+            replacement: Replacement::default(),
+            rave: false,
+            rave_equivalence: 1000.0,
+            threads: 1,
+            parallelism: Parallelism::default(),
+            virtual_loss: 1,
+            reuse_tree: true,
+            ponder: false,
+            c_puct: 1.5,
+            dirichlet_alpha: 0.3,
+            noise_fraction: 0.0,
+            temperature: 0.0,
+            batch_size: 8,
+        }
+    }
+}
+
+impl MctsConfig {
+    /// Reads `<prefix><field>` keys on top of `self`; the playout policy uses
+    /// `<prefix>playout.` keys.
+    pub fn from_config(mut self, config: &ConfigFile, prefix: &str) -> Result<Self, ConfigError> {
+        if let Some(value) = config.get(&format!("{prefix}playouts"))? {
+            self.playouts = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}exploration"))? {
+            self.exploration = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}transpositions"))? {
+            self.transpositions = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}memory_mb"))? {
+            self.memory_mb = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}rave"))? {
+            self.rave = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}rave_equivalence"))? {
+            self.rave_equivalence = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}threads"))? {
+            self.threads = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}virtual_loss"))? {
+            self.virtual_loss = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}reuse_tree"))? {
+            self.reuse_tree = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}ponder"))? {
+            self.ponder = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}c_puct"))? {
+            self.c_puct = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}dirichlet_alpha"))? {
+            self.dirichlet_alpha = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}noise_fraction"))? {
+            self.noise_fraction = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}temperature"))? {
+            self.temperature = value;
+        }
+        if let Some(value) = config.get(&format!("{prefix}batch_size"))? {
+            self.batch_size = value;
+        }
+        let key = format!("{prefix}parallelism");
+        if let Some(name) = config.raw(&key) {
+            self.parallelism = match name {
+                "tree" => Parallelism::Tree,
+                "root" => Parallelism::Root,
+                _ => {
+                    return Err(ConfigError::InvalidValue {
+                        key,
+                        value: name.to_string(),
+                    });
+                }
+            };
+        }
+        let key = format!("{prefix}replacement");
+        if let Some(name) = config.raw(&key) {
+            self.replacement = match name {
+                "always" => Replacement::Always,
+                "prefer_visited" => Replacement::PreferVisited,
+                _ => {
+                    return Err(ConfigError::InvalidValue {
+                        key,
+                        value: name.to_string(),
+                    });
+                }
+            };
+        }
+        self.policy = self
+            .policy
+            .from_config(config, &format!("{prefix}playout."))?;
+        Ok(self)
+    }
+
+    pub fn write_config(&self, config: &mut ConfigFile, prefix: &str) {
+        config.set(&format!("{prefix}playouts"), self.playouts);
+        config.set(&format!("{prefix}exploration"), self.exploration);
+        config.set(&format!("{prefix}transpositions"), self.transpositions);
+        config.set(&format!("{prefix}memory_mb"), self.memory_mb);
+        config.set(&format!("{prefix}rave"), self.rave);
+        config.set(&format!("{prefix}rave_equivalence"), self.rave_equivalence);
+        config.set(&format!("{prefix}threads"), self.threads);
+        config.set(&format!("{prefix}virtual_loss"), self.virtual_loss);
+        config.set(&format!("{prefix}reuse_tree"), self.reuse_tree);
+        config.set(&format!("{prefix}ponder"), self.ponder);
+        config.set(&format!("{prefix}c_puct"), self.c_puct);
+        config.set(&format!("{prefix}dirichlet_alpha"), self.dirichlet_alpha);
+        config.set(&format!("{prefix}noise_fraction"), self.noise_fraction);
+        config.set(&format!("{prefix}temperature"), self.temperature);
+        config.set(&format!("{prefix}batch_size"), self.batch_size);
+        config.set(
+            &format!("{prefix}parallelism"),
+            match self.parallelism {
+                Parallelism::Tree => "tree",
+                Parallelism::Root => "root",
+            },
+        );
+        config.set(
+            &format!("{prefix}replacement"),
+            match self.replacement {
+                Replacement::Always => "always",
+                Replacement::PreferVisited => "prefer_visited",
+            },
+        );
+        self.policy
+            .write_config(config, &format!("{prefix}playout."));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub playouts: usize,
+    /// Nodes held by the table after the search.
+    pub nodes: usize,
+    pub memory: usize,
+    /// Positions reached again through another move order. With
+    /// transpositions on these share a node; otherwise each is a duplicate.
+    pub transpositions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// `None` means pass.
+    pub best: Option<Position>,
+    /// Root moves, most visited first.
+    pub moves: Vec<Edge>,
+    pub stats: SearchStats,
+}
+
+/// Monte Carlo tree search with UCT selection and random playouts, or with
+/// a network, PUCT selection over its policy and its value in place of
+/// playouts. Nodes live in a transposition table keyed by the position, the
+/// side to move, a pending pass and the ko point. Searches with more than one
+/// thread are not reproducible from the seed.
+pub struct MctsAI {
+    pub config: MctsConfig,
+    table: Arc<TranspositionTable>,
+    /// Board size `table` is sized for.
+    table_size: usize,
+    network: Option<Arc<dyn Evaluator>>,
+    patterns: Option<Arc<PatternPriors>>,
+    pondering: Option<Pondering>,
+    rng: StdRng,
+}
+
+/// A search running in the background on the opponent's time.
+struct Pondering {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl MctsAI {
+    pub fn new(config: MctsConfig, seed: u64) -> Self {
+        MctsAI {
+            table: Arc::new(table_for(&config, DEFAULT_SIZE, 1)),
+            table_size: DEFAULT_SIZE,
+            config,
+            network: None,
+            patterns: None,
+            pondering: None,
+            rng: seed::rng(seed),
+        }
+    }
+
+    /// A PUCT search guided by `network`.
+    pub fn with_network(config: MctsConfig, network: Arc<dyn Evaluator>, seed: u64) -> Self {
+        let mut ai = Self::new(config, seed);
+        ai.network = Some(network);
+        ai
+    }
+
+    /// Expands moves strongest pattern first, so that UCT tries them first.
+    pub fn with_patterns(mut self, patterns: Arc<PatternPriors>) -> Self {
+        self.patterns = Some(patterns);
+        self
+    }
+
+    pub fn search(&mut self, board: &Board, player: Stone) -> SearchResult {
+        self.stop_pondering();
+        self.fit_table(board);
+        if self.config.reuse_tree {
+            self.table.new_generation();
+        } else {
+            self.table.clear();
+        }
+        let root = position_key(board, player, 0);
+        let threads = self.config.threads.max(1);
+        let seeds = self.thread_seeds();
+        let shared = Shared::default();
+
+        let (mut moves, nodes, memory) = match self.config.parallelism {
+            Parallelism::Tree => {
+                let network = self.network.as_deref();
+                let patterns = self.patterns.as_deref();
+                if network.is_some() && self.config.noise_fraction > 0.0 {
+                    let seed = self.rng.random();
+                    Worker::new(&self.config, &self.table, &shared, network, patterns, seed)
+                        .add_root_noise(board, player, root);
+                }
+                let budget = AtomicUsize::new(self.config.playouts);
+                let stop = AtomicBool::new(false);
+                grow(
+                    &self.config,
+                    &self.table,
+                    network,
+                    patterns,
+                    board,
+                    player,
+                    &seeds,
+                    &budget,
+                    &stop,
+                    &shared,
+                );
+                let moves = self
+                    .table
+                    .with(root, |node| node.edges.clone())
+                    .unwrap_or_default();
+                (moves, self.table.len(), self.table.memory_usage())
+            }
+            Parallelism::Root => {
+                let config = &self.config;
+                let network = self.network.as_deref();
+                let patterns = self.patterns.as_deref();
+                let shared = &shared;
+                let results: Vec<_> = std::thread::scope(|scope| {
+                    let handles: Vec<_> = seeds
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &seed)| {
+                            let playouts = config.playouts / threads
+                                + usize::from(i < config.playouts % threads);
+                            scope.spawn(move || {
+                                let table = table_for(config, board.size(), threads);
+                                table.pin(root);
+                                let mut worker =
+                                    Worker::new(config, &table, shared, network, patterns, seed);
+                                if network.is_some() && config.noise_fraction > 0.0 {
+                                    worker.add_root_noise(board, player, root);
+                                }
+                                let budget = AtomicUsize::new(playouts);
+                                while worker.step(board, player, root, &budget) {}
+                                let edges = table
+                                    .with(root, |node| node.edges.clone())
+                                    .unwrap_or_default();
+                                (edges, table.len(), table.memory_usage())
+                            })
+                        })
+                        .collect();
+                    handles
+                        .into_iter()
+                        .map(|handle| handle.join().expect("search thread panicked"))
+                        .collect()
+                });
+                let mut moves: Vec<Edge> = Vec::new();
+                for (edges, _, _) in &results {
+                    merge_edges(&mut moves, edges);
+                }
+                let nodes = results.iter().map(|r| r.1).sum();
+                let memory = results.iter().map(|r| r.2).sum();
+                (moves, nodes, memory)
+            }
+        };
+
+        moves.sort_by_key(|edge| Reverse(edge.visits));
+        SearchResult {
+            best: moves.first().and_then(|edge| edge.mv),
+            moves,
+            stats: SearchStats {
+                playouts: shared.playouts.load(Ordering::Relaxed),
+                nodes,
+                memory,
+                transpositions: shared.transpositions.load(Ordering::Relaxed),
+            },
+        }
+    }
+
+    /// Searches `board` with `player` to move in the background until
+    /// [`stop_pondering`](Self::stop_pondering) or the next search. Does
+    /// nothing unless pondering and tree reuse are on; root parallel searches
+    /// keep no tree to ponder into.
+    pub fn ponder(&mut self, board: &Board, player: Stone) {
+        self.stop_pondering();
+        if !self.config.ponder
+            || !self.config.reuse_tree
+            || self.config.parallelism == Parallelism::Root
+        {
+            return;
+        }
+
+        self.fit_table(board);
+        let stop = Arc::new(AtomicBool::new(false));
+        let config = self.config;
+        let table = Arc::clone(&self.table);
+        let network = self.network.clone();
+        let patterns = self.patterns.clone();
+        let board = board.clone();
+        let seeds = self.thread_seeds();
+        let flag = Arc::clone(&stop);
+        let thread = std::thread::spawn(move || {
+            table.new_generation();
+            let budget = AtomicUsize::new(config.playouts.saturating_mul(PONDER_LIMIT));
+            let shared = Shared::default();
+            grow(
+                &config,
+                &table,
+                network.as_deref(),
+                patterns.as_deref(),
+                &board,
+                player,
+                &seeds,
+                &budget,
+                &flag,
+                &shared,
+            );
+        });
+        self.pondering = Some(Pondering { stop, thread });
+    }
+
+    pub fn is_pondering(&self) -> bool {
+        self.pondering
+            .as_ref()
+            .is_some_and(|pondering| !pondering.thread.is_finished())
+    }
+
+    /// Stops a background search, waiting at most for its current playouts.
+    pub fn stop_pondering(&mut self) {
+        if let Some(pondering) = self.pondering.take() {
+            pondering.stop.store(true, Ordering::Relaxed);
+            let _ = pondering.thread.join();
+        }
+    }
+
+    /// The most visited move, or with a temperature a move drawn with
+    /// probability proportional to `visits^(1 / temperature)`.
+    pub fn choose(&mut self, result: &SearchResult) -> Option<Position> {
+        let temperature = self.config.temperature;
+        if temperature <= 0.0 {
+            return result.best;
+        }
+        let weights: Vec<f64> = result
+            .moves
+            .iter()
+            .map(|edge| (edge.visits as f64).powf(1.0 / temperature))
+            .collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 || !total.is_finite() {
+            return result.best;
+        }
+        let mut target = self.rng.random_range(0.0..total);
+        for (edge, weight) in result.moves.iter().zip(weights) {
+            if target < weight {
+                return edge.mv;
+            }
+            target -= weight;
+        }
+        result.best
+    }
+
+    /// Rebuilds the table, dropping its nodes, when the board size changes.
+    fn fit_table(&mut self, board: &Board) {
+        if self.table_size != board.size() {
+            self.table = Arc::new(table_for(&self.config, board.size(), 1));
+            self.table_size = board.size();
+        }
+    }
+
+    fn thread_seeds(&mut self) -> Vec<u64> {
+        (0..self.config.threads.max(1))
+            .map(|_| self.rng.random())
+            .collect()
+    }
+}
+
+impl Drop for MctsAI {
+    fn drop(&mut self) {
+        self.stop_pondering();
+    }
+}
+
+/// Runs playouts on the shared `table` from every seeded thread until the
+/// budget is spent or `stop` is raised.
+#[allow(clippy::too_many_arguments)]
+fn grow(
+    config: &MctsConfig,
+    table: &TranspositionTable,
+    network: Option<&dyn Evaluator>,
+    patterns: Option<&PatternPriors>,
+    board: &Board,
+    player: Stone,
+    seeds: &[u64],
+    budget: &AtomicUsize,
+    stop: &AtomicBool,
+    shared: &Shared,
+) {
+    let root = position_key(board, player, 0);
+    table.pin(root);
+    std::thread::scope(|scope| {
+        for &seed in seeds {
+            let mut worker = Worker::new(config, table, shared, network, patterns, seed);
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) && worker.step(board, player, root, budget) {}
+            });
+        }
+    });
+}
+
+/// Counters shared by the threads of one search.
+#[derive(Default)]
+struct Shared {
+    playouts: AtomicUsize,
+    transpositions: AtomicUsize,
+    /// Positions expanded by a tree search, to count duplicates.
+    expanded: Mutex<HashSet<u64>>,
+}
+
+/// One search thread: walks the shared graph from the root, expands a leaf,
+/// evaluates it with a playout or the network and backs the result up.
+struct Worker<'a> {
+    config: &'a MctsConfig,
+    table: &'a TranspositionTable,
+    shared: &'a Shared,
+    network: Option<&'a dyn Evaluator>,
+    patterns: Option<&'a PatternPriors>,
+    rng: StdRng,
+    /// Copy of the edges being selected from.
+    edges: Vec<Edge>,
+}
+
+/// Where a walk down the graph stopped.
+enum Leaf {
+    /// Both sides passed.
+    Finished(Stone),
+    /// A position without a node.
+    New,
+    /// The walk hit the depth limit or an illegal move; evaluated without
+    /// expanding.
+    Cut,
+}
+
+struct Descent {
+    /// `(key, edge index, move, mover)` of every step.
+    path: Vec<(u64, usize, Option<Position>, Stone)>,
+    played: Vec<(Stone, Position)>,
+    board: Board,
+    to_play: Stone,
+    key: u64,
+    leaf: Leaf,
+}
+
+impl<'a> Worker<'a> {
+    fn new(
+        config: &'a MctsConfig,
+        table: &'a TranspositionTable,
+        shared: &'a Shared,
+        network: Option<&'a dyn Evaluator>,
+        patterns: Option<&'a PatternPriors>,
+        seed: u64,
+    ) -> Self {
+        Worker {
+            config,
+            table,
+            shared,
+            network,
+            patterns,
+            rng: seed::rng(seed),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Runs one playout, or one batch with a network. Returns `false` once
+    /// the budget is spent.
+    fn step(&mut self, root: &Board, player: Stone, root_key: u64, budget: &AtomicUsize) -> bool {
+        match self.network {
+            None => {
+                if claim(budget, 1).is_none() {
+                    return false;
+                }
+                self.simulate(root, player, root_key);
+            }
+            Some(network) => {
+                let Some(count) = claim(budget, self.config.batch_size.max(1)) else {
+                    return false;
+                };
+                self.simulate_batch(network, root, player, root_key, count);
+            }
+        }
+        true
+    }
+
+    fn simulate(&mut self, root: &Board, player: Stone, root_key: u64) {
+        let mut descent = self.descend(root, player, root_key);
+        let winner = match descent.leaf {
+            Leaf::Finished(winner) => winner,
+            Leaf::New => {
+                let edges = self.expand(&descent.board, descent.to_play);
+                self.insert(descent.key, edges);
+                self.rollout(&descent.board, descent.to_play, &mut descent.played)
+            }
+            Leaf::Cut => self.rollout(&descent.board, descent.to_play, &mut descent.played),
+        };
+        self.backup(descent, if winner == Stone::Black { 1.0 } else { 0.0 });
+    }
+
+    /// Walks down `count` times, with virtual loss spreading the walks, then
+    /// evaluates all new leaves in one network call.
+    fn simulate_batch(
+        &mut self,
+        network: &dyn Evaluator,
+        root: &Board,
+        player: Stone,
+        root_key: u64,
+        count: usize,
+    ) {
+        let descents: Vec<Descent> = (0..count)
+            .map(|_| self.descend(root, player, root_key))
+            .collect();
+        let batch: Vec<(&Board, Stone)> = descents
+            .iter()
+            .filter(|descent| !matches!(descent.leaf, Leaf::Finished(_)))
+            .map(|descent| (&descent.board, descent.to_play))
+            .collect();
+        let mut evaluations = network.evaluate(&batch).into_iter();
+
+        for descent in descents {
+            let black = match descent.leaf {
+                Leaf::Finished(winner) => {
+                    if winner == Stone::Black {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+                Leaf::New | Leaf::Cut => {
+                    let evaluation = evaluations
+                        .next()
+                        .expect("the network evaluates the whole batch");
+                    if matches!(descent.leaf, Leaf::New) {
+                        let mut edges = self.expand(&descent.board, descent.to_play);
+                        set_priors(&mut edges, &evaluation.policy, descent.board.size());
+                        self.insert(descent.key, edges);
+                    }
+                    let win = (evaluation.value + 1.0) / 2.0;
+                    if descent.to_play == Stone::Black {
+                        win
+                    } else {
+                        1.0 - win
+                    }
+                }
+            };
+            self.backup(descent, black);
+        }
+    }
+
+    /// Follows the selection rule from the root, applying virtual loss, until
+    /// it leaves the stored graph or the game ends.
+    fn descend(&mut self, root: &Board, player: Stone, root_key: u64) -> Descent {
+        let size = root.size();
+        let virtual_loss = self.config.virtual_loss;
+        let generation = self.table.generation();
+        let puct = self.network.is_some();
+        let mut board = root.clone();
+        let mut to_play = player;
+        let mut passes = 0;
+        let mut key = root_key;
+        let mut path = Vec::new();
+        let mut played = Vec::new();
+
+        let leaf = loop {
+            if passes >= 2 {
+                break Leaf::Finished(area_winner(&board, self.config.komi));
+            }
+            if path.len() >= size * size * 2 {
+                break Leaf::Cut;
+            }
+
+            // Select on a copy of the edges so that other threads can use
+            // the node meanwhile; the root would otherwise serialise them.
+            let edges = &mut self.edges;
+            let Some(visits) = self.table.with(key, |node| {
+                // Nodes reused from an earlier search belong to this one now.
+                node.generation = generation;
+                edges.clear();
+                edges.extend_from_slice(&node.edges);
+                node.visits
+            }) else {
+                if !self.config.transpositions
+                    && !lock(&self.shared.expanded).insert(position_key(&board, to_play, passes))
+                {
+                    self.shared.transpositions.fetch_add(1, Ordering::Relaxed);
+                }
+                break Leaf::New;
+            };
+            let (index, mv, first_visit) = if puct {
+                select_puct(visits, &self.edges, self.config)
+            } else {
+                select(visits, &self.edges, self.config)
+            };
+            self.table.with(key, |node| {
+                node.visits += virtual_loss;
+                if let Some(edge) = node.edges.get_mut(index)
+                    && edge.mv == mv
+                {
+                    edge.visits += virtual_loss;
+                }
+            });
+
+            path.push((key, index, mv, to_play));
+            match mv {
+                Some(pos) if board.play(pos, to_play).is_err() => break Leaf::Cut,
+                Some(pos) => {
+                    played.push((to_play, pos));
+                    passes = 0;
+                }
+                None => {
+                    board.pass();
+                    passes += 1;
+                }
+            }
+            to_play = to_play.opposite();
+
+            key = if self.config.transpositions {
+                position_key(&board, to_play, passes)
+            } else {
+                let code = mv.map_or(size * size, |pos| board.pos_to_index(pos));
+                seed::derive(key, code as u64)
+            };
+            if first_visit && self.table.with(key, |_| ()).is_some() {
+                self.shared.transpositions.fetch_add(1, Ordering::Relaxed);
+            }
+        };
+
+        Descent {
+            path,
+            played,
+            board,
+            to_play,
+            key,
+            leaf,
+        }
+    }
+
+    /// Replaces the virtual loss along the path with the result; `black` is
+    /// Black's share of the win.
+    fn backup(&mut self, descent: Descent, black: f32) {
+        let size = descent.board.size();
+        let virtual_loss = self.config.virtual_loss;
+        self.table.with(descent.key, |node| node.visits += 1);
+        let mut depth = 0;
+        for (key, index, mv, mover) in descent.path {
+            let win = if mover == Stone::Black {
+                black
+            } else {
+                1.0 - black
+            };
+            let amaf = self
+                .config
+                .rave
+                .then(|| first_plays(&descent.played[depth..], mover, size));
+            self.table.with(key, |node| {
+                node.visits = (node.visits + 1).saturating_sub(virtual_loss);
+                if let Some(edge) = node.edges.get_mut(index)
+                    && edge.mv == mv
+                {
+                    edge.visits = (edge.visits + 1).saturating_sub(virtual_loss);
+                    edge.wins += win;
+                }
+                if let Some(amaf) = &amaf {
+                    for edge in &mut node.edges {
+                        if let Some(pos) = edge.mv
+                            && amaf[pos.x + pos.y * size]
+                        {
+                            edge.amaf_visits += 1;
+                            edge.amaf_wins += win;
+                        }
+                    }
+                }
+            });
+            if mv.is_some() {
+                depth += 1;
+            }
+        }
+        self.shared.playouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Expands the root from the network if needed and perturbs its priors.
+    fn add_root_noise(&mut self, board: &Board, player: Stone, key: u64) {
+        let Some(network) = self.network else {
+            return;
+        };
+        if self.table.with(key, |_| ()).is_none() {
+            let evaluation = network.evaluate(&[(board, player)]).remove(0);
+            let mut edges = self.expand(board, player);
+            set_priors(&mut edges, &evaluation.policy, board.size());
+            self.insert(key, edges);
+        }
+        let (alpha, fraction) = (self.config.dirichlet_alpha, self.config.noise_fraction);
+        let rng = &mut self.rng;
+        self.table
+            .with(key, |node| add_noise(&mut node.edges, alpha, fraction, rng));
+    }
+
+    fn insert(&self, key: u64, edges: Vec<Edge>) {
+        self.table.insert(Node {
+            key,
+            generation: 0,
+            visits: 0,
+            edges,
+        });
+    }
+
+    /// Legal moves that do not fill a real eye, in random order or with
+    /// pattern priors strongest first, plus pass.
+    fn expand(&mut self, board: &Board, player: Stone) -> Vec<Edge> {
+        let size = board.size();
+        let mut edges: Vec<Edge> = (0..size * size)
+            .map(|i| Position {
+                x: i % size,
+                y: i / size,
+            })
+            .filter(|&pos| {
+                board.get_stone(pos).ok() == Some(None)
+                    && eye::classify(board, pos, player) != EyeKind::Real
+                    && board.is_valid_move(pos, player)
+            })
+            .map(|pos| Edge::new(Some(pos)))
+            .collect();
+        edges.shuffle(&mut self.rng);
+        if let Some(patterns) = self.patterns {
+            let mut scored: Vec<(f64, Edge)> = edges
+                .into_iter()
+                .map(|edge| {
+                    let pos = edge.mv.expect("only the pass edge has no move");
+                    (patterns.strength(board, pos, player), edge)
+                })
+                .collect();
+            // Stable, so equally strong moves stay in random order.
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+            edges = scored.into_iter().map(|(_, edge)| edge).collect();
+        }
+        edges.push(Edge::new(None));
+        edges
+    }
+
+    fn rollout(
+        &mut self,
+        board: &Board,
+        to_play: Stone,
+        played: &mut Vec<(Stone, Position)>,
+    ) -> Stone {
+        let mut board = board.clone();
+        let size = board.size();
+        let record = self.config.rave;
+        playout::playout_with(
+            &mut board,
+            to_play,
+            &self.config.policy,
+            size * size * 3,
+            &mut self.rng,
+            |stone, pos| {
+                if record {
+                    played.push((stone, pos));
+                }
+            },
+        );
+        area_winner(&board, self.config.komi)
+    }
+}
+
+/// Copies the policy onto the edges, renormalised over the moves they cover.
+fn set_priors(edges: &mut [Edge], policy: &[f32], size: usize) {
+    for edge in edges.iter_mut() {
+        edge.policy = match edge.mv {
+            Some(pos) => policy[pos.x + pos.y * size],
+            None => policy[size * size],
+        };
+    }
+    let total: f32 = edges.iter().map(|edge| edge.policy).sum();
+    let count = edges.len() as f32;
+    for edge in edges {
+        edge.policy = if total > 0.0 {
+            edge.policy / total
+        } else {
+            1.0 / count
+        };
+        edge.prior = edge.policy;
+    }
+}
+
+/// Mixes fresh Dirichlet noise into the policy so that self-play explores
+/// moves the network dislikes. A root kept from an earlier search gets new
+/// noise rather than noise on top of the old.
+fn add_noise(edges: &mut [Edge], alpha: f64, fraction: f64, rng: &mut StdRng) {
+    let noise: Vec<f64> = edges.iter().map(|_| gamma(alpha, rng)).collect();
+    let total: f64 = noise.iter().sum();
+    if total <= 0.0 {
+        return;
+    }
+    for (edge, noise) in edges.iter_mut().zip(noise) {
+        edge.prior = ((1.0 - fraction) * edge.policy as f64 + fraction * noise / total) as f32;
+    }
+}
+
+/// A Gamma(`alpha`, 1) sample by Marsaglia and Tsang's method.
+fn gamma(alpha: f64, rng: &mut StdRng) -> f64 {
+    if alpha < 1.0 {
+        let boost = rng.random::<f64>().powf(1.0 / alpha);
+        return gamma(alpha + 1.0, rng) * boost;
+    }
+    let d = alpha - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        // Box-Muller normal sample.
+        let u1: f64 = 1.0 - rng.random::<f64>();
+        let u2: f64 = rng.random();
+        let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        let v = (1.0 + c * z).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u: f64 = 1.0 - rng.random::<f64>();
+        if u.ln() < 0.5 * z * z + d - d * v + d * v.ln() {
+            return d * v;
+        }
+    }
+}
+
+/// One thread's share of the memory budget, sized for nodes with an edge
+/// for every point and the pass.
+fn table_for(config: &MctsConfig, size: usize, threads: usize) -> TranspositionTable {
+    TranspositionTable::with_memory(
+        (config.memory_mb << 20) / threads,
+        size * size + 1,
+        config.replacement,
+    )
+}
+
+/// Takes up to `count` playouts from the budget; `None` once it is spent.
+fn claim(remaining: &AtomicUsize, count: usize) -> Option<usize> {
+    remaining
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+            n.checked_sub(count.min(n)).filter(|_| n > 0)
+        })
+        .ok()
+        .map(|n| count.min(n))
+}
+
+/// Adds the statistics of `edges` to the matching moves of `total`.
+fn merge_edges(total: &mut Vec<Edge>, edges: &[Edge]) {
+    for edge in edges {
+        match total.iter_mut().find(|e| e.mv == edge.mv) {
+            Some(sum) => {
+                sum.visits += edge.visits;
+                sum.wins += edge.wins;
+                sum.amaf_visits += edge.amaf_visits;
+                sum.amaf_wins += edge.amaf_wins;
+            }
+            None => total.push(*edge),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl GoAI for MctsAI {
+    fn select_move(&mut self, board: &Board, player: Stone) -> Option<Position> {
+        let result = self.search(board, player);
+        self.choose(&result)
+    }
+
+    fn ponder(&mut self, board: &Board, player: Stone) {
+        MctsAI::ponder(self, board, player);
+    }
+
+    fn stop_pondering(&mut self) {
+        MctsAI::stop_pondering(self);
+    }
+
+    fn is_pondering(&self) -> bool {
+        MctsAI::is_pondering(self)
+    }
+}
+
+/// Picks the edge with the best UCT value, or with RAVE the best blend of
+/// UCT and AMAF values; the pass keeps its UCT value. Without RAVE unvisited
+/// edges come first. Returns the edge index, its move and whether this is
+/// its first visit.
+fn select(visits: u32, edges: &[Edge], config: &MctsConfig) -> (usize, Option<Position>, bool) {
+    if !config.rave
+        && let Some(index) = edges.iter().position(|edge| edge.visits == 0)
+    {
+        return (index, edges[index].mv, true);
+    }
+
+    let log_visits = (visits.max(1) as f64).ln();
+    let value = |edge: &Edge| {
+        let visits = edge.visits as f64;
+        if !config.rave {
+            return edge.wins as f64 / visits + config.exploration * (log_visits / visits).sqrt();
+        }
+
+        let uct = if edge.visits == 0 {
+            0.0
+        } else {
+            edge.wins as f64 / visits
+        };
+        // Playouts never record a pass, so it has no AMAF statistics.
+        if edge.mv.is_none() {
+            return uct + config.exploration * (log_visits / (visits + 1.0)).sqrt();
+        }
+        // Unseen moves are tried optimistically.
+        let amaf = match edge.amaf_visits {
+            0 => 1.0,
+            n => edge.amaf_wins as f64 / n as f64,
+        };
+        let k = config.rave_equivalence;
+        let beta = (k / (3.0 * visits + k)).sqrt();
+        (1.0 - beta) * uct + beta * amaf + config.exploration * (log_visits / (visits + 1.0)).sqrt()
+    };
+    let (index, edge) = edges
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| value(a).total_cmp(&value(b)))
+        .expect("nodes always have the pass edge");
+    (index, edge.mv, edge.visits == 0)
+}
+
+/// Picks the edge maximising `Q + c * P * sqrt(N) / (1 + n)`. Unvisited
+/// edges count as even.
+fn select_puct(
+    visits: u32,
+    edges: &[Edge],
+    config: &MctsConfig,
+) -> (usize, Option<Position>, bool) {
+    let scale = config.c_puct * (visits.max(1) as f64).sqrt();
+    let value = |edge: &Edge| {
+        let q = match edge.visits {
+            0 => 0.5,
+            n => edge.wins as f64 / n as f64,
+        };
+        q + scale * edge.prior as f64 / (1.0 + edge.visits as f64)
+    };
+    let (index, edge) = edges
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| value(a).total_cmp(&value(b)))
+        .expect("nodes always have the pass edge");
+    (index, edge.mv, edge.visits == 0)
+}
+
+/// Points that `mover` played first in `moves`, i.e. before either colour
+/// played there, indexed like `Board::pos_to_index`.
+fn first_plays(moves: &[(Stone, Position)], mover: Stone, size: usize) -> Vec<bool> {
+    let mut seen = vec![false; size * size];
+    let mut first = vec![false; size * size];
+    for &(stone, pos) in moves {
+        let index = pos.x + pos.y * size;
+        if !seen[index] {
+            seen[index] = true;
+            first[index] = stone == mover;
+        }
+    }
+    first
+}
+
+/// Deterministic key of a search position: the Zobrist hash plus the side to
+/// move, whether the previous move was a pass, and the ko state.
+pub fn position_key(board: &Board, to_play: Stone, passes: usize) -> u64 {
+    let mut key = board.hash();
+    if to_play == Stone::White {
+        key ^= seed::mix(WHITE_TO_PLAY);
+    }
+    if passes > 0 {
+        key ^= seed::mix(PASSED);
+    }
+    key ^ board.ko_key()
+}
+
+/// Area score of a finished playout: stones plus single-point eyes.
+fn area_winner(board: &Board, komi: f32) -> Stone {
+    let size = board.size();
+    let black: f32 = (0..size * size)
+        .map(|i| {
+            area_owner(
+                board,
+                Position {
+                    x: i % size,
+                    y: i / size,
+                },
+            )
+        })
+        .sum();
+    if black > komi {
+        Stone::Black
+    } else {
+        Stone::White
+    }
+}
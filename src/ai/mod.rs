@@ -1,8 +1,54 @@
 use crate::core::{Board, Position, Stone};
 
+pub mod book;
+pub mod features;
 pub mod heuristic;
+pub mod mcts;
+pub mod network;
+pub mod ownership;
+pub mod patterns;
+pub mod playout;
 pub mod random;
+pub mod train;
+pub mod transposition;
+pub mod tuning;
 
 pub trait GoAI {
-    fn select_move(&self, board: &Board, player: Stone) -> Option<Position>;
+    fn select_move(&mut self, board: &Board, player: Stone) -> Option<Position>;
+
+    fn move_probabilities(&self, _board: &Board, _player: Stone) -> Option<Vec<f32>> {
+        None
+    }
+
+    /// Starts thinking in the background while `player`, the opponent, is
+    /// to move on `board`. Engines that cannot ponder ignore it.
+    fn ponder(&mut self, _board: &Board, _player: Stone) {}
+
+    fn stop_pondering(&mut self) {}
+
+    fn is_pondering(&self) -> bool {
+        false
+    }
+}
+
+impl<A: GoAI + ?Sized> GoAI for Box<A> {
+    fn select_move(&mut self, board: &Board, player: Stone) -> Option<Position> {
+        (**self).select_move(board, player)
+    }
+
+    fn move_probabilities(&self, board: &Board, player: Stone) -> Option<Vec<f32>> {
+        (**self).move_probabilities(board, player)
+    }
+
+    fn ponder(&mut self, board: &Board, player: Stone) {
+        (**self).ponder(board, player)
+    }
+
+    fn stop_pondering(&mut self) {
+        (**self).stop_pondering()
+    }
+
+    fn is_pondering(&self) -> bool {
+        (**self).is_pondering()
+    }
 }
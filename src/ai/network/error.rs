@@ -0,0 +1,38 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum NetworkError {
+    #[error("Not a network weights file")]
+    BadMagic,
+
+    #[error("Unsupported network weights version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Invalid network architecture: {0}")]
+    InvalidArchitecture(String),
+
+    #[error("Network reads {found} input planes, this build encodes {expected}")]
+    PlaneMismatch { expected: usize, found: usize },
+
+    #[error("Tensor {tensor} holds {found} values, the architecture needs {expected}")]
+    Shape {
+        tensor: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Tensor {tensor} holds a value that is not finite")]
+    NonFinite { tensor: String },
+
+    #[error("Network weights are cut off in {tensor}")]
+    Truncated { tensor: String },
+
+    #[error("Network weights continue after the last tensor")]
+    TrailingData,
+
+    #[error("Network is built for {expected}x{expected} boards, not {found}x{found}")]
+    BoardSize { expected: usize, found: usize },
+
+    #[error("Failed to access network weights: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,570 @@
+pub mod error;
+
+pub use error::NetworkError;
+
+use crate::ai::GoAI;
+use crate::ai::features;
+use crate::ai::mcts::{MctsAI, MctsConfig};
+use crate::core::{Board, Position, Stone, seed};
+use rand::Rng;
+use rand::rngs::StdRng;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+const MAGIC: &[u8; 4] = b"BGNW";
+const VERSION: u32 = 1;
+const MAX_BOARD_SIZE: usize = 25;
+const MAX_CHANNELS: usize = 1024;
+const MAX_BLOCKS: usize = 64;
+const MAX_VALUE_HIDDEN: usize = 1024;
+
+/// The weights and bias of every layer of `$network`, named and in file
+/// order, taken with `$borrow` (`&` or `&mut`). Writing and reading both go
+/// through this one list, so they cannot disagree on the layout.
+macro_rules! tensors {
+    ($network:expr, $($borrow:tt)+) => {{
+        let network = $network;
+        let mut layers = vec![(
+            "input".to_string(),
+            $($borrow)+ network.input.weights,
+            $($borrow)+ network.input.bias,
+        )];
+        for (i, [first, second]) in ($($borrow)+ network.blocks).into_iter().enumerate() {
+            layers.push((
+                format!("blocks.{i}.0"),
+                $($borrow)+ first.weights,
+                $($borrow)+ first.bias,
+            ));
+            layers.push((
+                format!("blocks.{i}.1"),
+                $($borrow)+ second.weights,
+                $($borrow)+ second.bias,
+            ));
+        }
+        layers.extend([
+            (
+                "policy_conv".to_string(),
+                $($borrow)+ network.policy_conv.weights,
+                $($borrow)+ network.policy_conv.bias,
+            ),
+            (
+                "policy".to_string(),
+                $($borrow)+ network.policy.weights,
+                $($borrow)+ network.policy.bias,
+            ),
+            (
+                "value_conv".to_string(),
+                $($borrow)+ network.value_conv.weights,
+                $($borrow)+ network.value_conv.bias,
+            ),
+            (
+                "value_hidden".to_string(),
+                $($borrow)+ network.value_hidden.weights,
+                $($borrow)+ network.value_hidden.bias,
+            ),
+            (
+                "value".to_string(),
+                $($borrow)+ network.value.weights,
+                $($borrow)+ network.value.bias,
+            ),
+        ]);
+        layers
+            .into_iter()
+            .flat_map(|(name, weights, bias)| {
+                [
+                    (format!("{name}.weights"), weights),
+                    (format!("{name}.bias"), bias),
+                ]
+            })
+            .collect()
+    }};
+}
+
+/// What the network makes of one position, seen from the side to move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// Move probabilities indexed like `Board::pos_to_index`, pass last.
+    pub policy: Vec<f32>,
+    /// Expected outcome in `-1..=1`; 1 is a win for the side to move.
+    pub value: f32,
+}
+
+/// Scores positions for PUCT search. Positions arrive in batches so that an
+/// implementation can spread fixed costs over several leaves.
+pub trait Evaluator: Send + Sync {
+    fn evaluate(&self, batch: &[(&Board, Stone)]) -> Vec<Evaluation>;
+}
+
+/// Shape of a residual policy/value network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Architecture {
+    pub board_size: usize,
+    pub input_planes: usize,
+    pub channels: usize,
+    /// Residual blocks of two 3x3 convolutions each.
+    pub blocks: usize,
+    /// Width of the hidden layer of the value head.
+    pub value_hidden: usize,
+}
+
+impl Architecture {
+    pub fn new(board_size: usize) -> Self {
+        Architecture {
+            board_size,
+            input_planes: features::PLANES,
+            channels: 32,
+            blocks: 4,
+            value_hidden: 64,
+        }
+    }
+
+    /// Checks that `Network` can run this shape with the current input
+    /// features.
+    pub fn validate(&self) -> Result<(), NetworkError> {
+        if !(1..=MAX_BOARD_SIZE).contains(&self.board_size) {
+            return Err(NetworkError::InvalidArchitecture(format!(
+                "board size {}",
+                self.board_size
+            )));
+        }
+        if !(1..=MAX_CHANNELS).contains(&self.channels)
+            || self.blocks > MAX_BLOCKS
+            || !(1..=MAX_VALUE_HIDDEN).contains(&self.value_hidden)
+        {
+            return Err(NetworkError::InvalidArchitecture(format!(
+                "{} channels, {} blocks and a value head of {}",
+                self.channels, self.blocks, self.value_hidden
+            )));
+        }
+        if self.input_planes != features::PLANES {
+            return Err(NetworkError::PlaneMismatch {
+                expected: features::PLANES,
+                found: self.input_planes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Number of values in each tensor, in file order, or `None` when one
+    /// does not fit in a `usize`.
+    fn tensor_lengths(&self) -> Option<Vec<usize>> {
+        let area = self.board_size.checked_mul(self.board_size)?;
+        let conv = |inputs: usize, outputs: usize, kernel: usize| {
+            Some([
+                inputs.checked_mul(kernel * kernel)?.checked_mul(outputs)?,
+                outputs,
+            ])
+        };
+        let dense = |inputs: usize, outputs: usize| Some([inputs.checked_mul(outputs)?, outputs]);
+
+        let mut lengths = Vec::new();
+        lengths.extend(conv(self.input_planes, self.channels, 3)?);
+        for _ in 0..self.blocks * 2 {
+            lengths.extend(conv(self.channels, self.channels, 3)?);
+        }
+        lengths.extend(conv(self.channels, 2, 1)?);
+        lengths.extend(dense(area.checked_mul(2)?, area.checked_add(1)?)?);
+        lengths.extend(conv(self.channels, 1, 1)?);
+        lengths.extend(dense(area, self.value_hidden)?);
+        lengths.extend(dense(self.value_hidden, 1)?);
+        Some(lengths)
+    }
+}
+
+/// A convolution over the whole board with zero padding. Batch normalisation
+/// is folded into the weights and bias.
+#[derive(Debug, Clone, PartialEq)]
+struct Conv {
+    inputs: usize,
+    outputs: usize,
+    kernel: usize,
+    /// `[output][input][ky][kx]`.
+    weights: Vec<f32>,
+    bias: Vec<f32>,
+}
+
+impl Conv {
+    fn new(inputs: usize, outputs: usize, kernel: usize, init: &mut Init) -> Self {
+        Conv {
+            inputs,
+            outputs,
+            kernel,
+            weights: init(inputs * kernel * kernel, outputs),
+            bias: vec![0.0; outputs],
+        }
+    }
+
+    /// `input` and the result are `[channel][y][x]`.
+    fn forward(&self, input: &[f32], size: usize) -> Vec<f32> {
+        let area = size * size;
+        let half = (self.kernel / 2) as isize;
+        let mut output = vec![0.0; self.outputs * area];
+        for (o, plane) in output.chunks_exact_mut(area).enumerate() {
+            plane.fill(self.bias[o]);
+            for i in 0..self.inputs {
+                let source = &input[i * area..(i + 1) * area];
+                for ky in 0..self.kernel {
+                    for kx in 0..self.kernel {
+                        let weight = self.weights
+                            [((o * self.inputs + i) * self.kernel + ky) * self.kernel + kx];
+                        let dy = ky as isize - half;
+                        let dx = kx as isize - half;
+                        for y in 0..size {
+                            let sy = y as isize + dy;
+                            if sy < 0 || sy >= size as isize {
+                                continue;
+                            }
+                            for x in 0..size {
+                                let sx = x as isize + dx;
+                                if sx >= 0 && sx < size as isize {
+                                    plane[y * size + x] +=
+                                        weight * source[sy as usize * size + sx as usize];
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Dense {
+    inputs: usize,
+    outputs: usize,
+    /// `[output][input]`.
+    weights: Vec<f32>,
+    bias: Vec<f32>,
+}
+
+impl Dense {
+    fn new(inputs: usize, outputs: usize, init: &mut Init) -> Self {
+        Dense {
+            inputs,
+            outputs,
+            weights: init(inputs, outputs),
+            bias: vec![0.0; outputs],
+        }
+    }
+
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.weights
+            .chunks_exact(self.inputs)
+            .zip(&self.bias)
+            .map(|(row, bias)| bias + row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>())
+            .collect()
+    }
+}
+
+/// Draws the weights of a layer from its fan-in and output count.
+type Init<'a> = dyn FnMut(usize, usize) -> Vec<f32> + 'a;
+
+/// Uniform weights with the variance of He initialisation.
+fn he_uniform(fan_in: usize, outputs: usize, rng: &mut StdRng) -> Vec<f32> {
+    let limit = (6.0 / fan_in as f32).sqrt();
+    (0..fan_in * outputs)
+        .map(|_| rng.random_range(-limit..limit))
+        .collect()
+}
+
+fn relu(values: &mut [f32]) {
+    for value in values {
+        *value = value.max(0.0);
+    }
+}
+
+/// An AlphaZero-style residual tower with a policy and a value head, run on
+/// the CPU.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Network {
+    architecture: Architecture,
+    input: Conv,
+    blocks: Vec<[Conv; 2]>,
+    policy_conv: Conv,
+    policy: Dense,
+    value_conv: Conv,
+    value_hidden: Dense,
+    value: Dense,
+}
+
+impl Network {
+    /// Untrained weights drawn from `seed`.
+    pub fn random(architecture: Architecture, seed: u64) -> Self {
+        let mut rng = seed::rng(seed);
+        Self::build(architecture, &mut |fan_in, outputs| {
+            he_uniform(fan_in, outputs, &mut rng)
+        })
+    }
+
+    fn build(architecture: Architecture, init: &mut Init) -> Self {
+        let Architecture {
+            board_size,
+            input_planes,
+            channels,
+            blocks,
+            value_hidden,
+        } = architecture;
+        let area = board_size * board_size;
+        Network {
+            architecture,
+            input: Conv::new(input_planes, channels, 3, init),
+            blocks: (0..blocks)
+                .map(|_| {
+                    [
+                        Conv::new(channels, channels, 3, init),
+                        Conv::new(channels, channels, 3, init),
+                    ]
+                })
+                .collect(),
+            policy_conv: Conv::new(channels, 2, 1, init),
+            policy: Dense::new(2 * area, area + 1, init),
+            value_conv: Conv::new(channels, 1, 1, init),
+            value_hidden: Dense::new(area, value_hidden, init),
+            value: Dense::new(value_hidden, 1, init),
+        }
+    }
+
+    pub fn architecture(&self) -> Architecture {
+        self.architecture
+    }
+
+    /// Evaluates one position. Panics if the board size differs from the
+    /// architecture's.
+    pub fn forward(&self, board: &Board, player: Stone) -> Evaluation {
+        let size = self.architecture.board_size;
+        assert_eq!(board.size(), size, "network built for {size}x{size} boards");
+
+        let mut x = self.input.forward(&features::encode(board, player), size);
+        relu(&mut x);
+        for [first, second] in &self.blocks {
+            let mut y = first.forward(&x, size);
+            relu(&mut y);
+            let mut y = second.forward(&y, size);
+            for (y, x) in y.iter_mut().zip(&x) {
+                *y += x;
+            }
+            relu(&mut y);
+            x = y;
+        }
+
+        let mut policy = self.policy_conv.forward(&x, size);
+        relu(&mut policy);
+        let policy = softmax(self.policy.forward(&policy));
+
+        let mut value = self.value_conv.forward(&x, size);
+        relu(&mut value);
+        let mut hidden = self.value_hidden.forward(&value);
+        relu(&mut hidden);
+        let value = self.value.forward(&hidden)[0].tanh();
+
+        Evaluation { policy, value }
+    }
+
+    /// Writes the weights in betago's versioned format, little endian:
+    ///
+    /// - the magic `BGNW` and the format version as `u32`;
+    /// - the architecture as five `u32`: board size, input planes, channels,
+    ///   residual blocks and value head width;
+    /// - every tensor as its number of values (`u32`) followed by that many
+    ///   `f32`.
+    ///
+    /// Tensors come weights first, then bias, for the input convolution,
+    /// both convolutions of each block in turn, the policy convolution and
+    /// layer, the value convolution and the two value layers. Convolution
+    /// weights are `[output][input][ky][kx]`, dense weights
+    /// `[output][input]`, and batch normalisation is folded in.
+    pub fn write(&self, out: &mut impl Write) -> Result<(), NetworkError> {
+        let architecture = self.architecture;
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        for value in [
+            architecture.board_size,
+            architecture.input_planes,
+            architecture.channels,
+            architecture.blocks,
+            architecture.value_hidden,
+        ] {
+            out.write_all(&(value as u32).to_le_bytes())?;
+        }
+        for (_, tensor) in self.tensors() {
+            out.write_all(&(tensor.len() as u32).to_le_bytes())?;
+            for value in tensor {
+                out.write_all(&value.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads weights written by `write`. The architecture must be one this
+    /// build can run and every tensor must have the shape it implies.
+    pub fn read(input: &mut impl Read) -> Result<Self, NetworkError> {
+        let header = |_| NetworkError::Truncated {
+            tensor: "header".to_string(),
+        };
+        let magic: [u8; 4] = read_array(input).map_err(header)?;
+        if &magic != MAGIC {
+            return Err(NetworkError::BadMagic);
+        }
+        let version = u32::from_le_bytes(read_array(input).map_err(header)?);
+        if version != VERSION {
+            return Err(NetworkError::UnsupportedVersion(version));
+        }
+        let mut fields = [0; 5];
+        for field in &mut fields {
+            *field = u32::from_le_bytes(read_array(input).map_err(header)?) as usize;
+        }
+        let [board_size, input_planes, channels, blocks, value_hidden] = fields;
+        let architecture = Architecture {
+            board_size,
+            input_planes,
+            channels,
+            blocks,
+            value_hidden,
+        };
+        architecture.validate()?;
+        let lengths = architecture.tensor_lengths().ok_or_else(|| {
+            NetworkError::InvalidArchitecture(format!("{architecture:?} is too large"))
+        })?;
+
+        // Weights are only allocated as their values arrive, so a header
+        // promising huge tensors cannot exhaust memory.
+        let mut network = Self::build(architecture, &mut |_, _| Vec::new());
+        for ((name, tensor), expected) in network.tensors_mut().into_iter().zip(lengths) {
+            let truncated = |e: std::io::Error| match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => NetworkError::Truncated {
+                    tensor: name.clone(),
+                },
+                _ => NetworkError::Io(e),
+            };
+            let found = u32::from_le_bytes(read_array(input).map_err(truncated)?) as usize;
+            if found != expected {
+                return Err(NetworkError::Shape {
+                    tensor: name,
+                    expected,
+                    found,
+                });
+            }
+            tensor.clear();
+            for _ in 0..expected {
+                tensor.push(f32::from_le_bytes(read_array(input).map_err(truncated)?));
+            }
+            if !tensor.iter().all(|value| value.is_finite()) {
+                return Err(NetworkError::NonFinite { tensor: name });
+            }
+        }
+        if input.read(&mut [0])? != 0 {
+            return Err(NetworkError::TrailingData);
+        }
+        Ok(network)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), NetworkError> {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write(&mut out)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NetworkError> {
+        Self::read(&mut std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+
+    /// Every tensor with its name, in file order.
+    fn tensors(&self) -> Vec<(String, &Vec<f32>)> {
+        tensors!(self, &)
+    }
+
+    fn tensors_mut(&mut self) -> Vec<(String, &mut Vec<f32>)> {
+        tensors!(self, &mut)
+    }
+}
+
+impl Evaluator for Network {
+    fn evaluate(&self, batch: &[(&Board, Stone)]) -> Vec<Evaluation> {
+        batch
+            .iter()
+            .map(|&(board, player)| self.forward(board, player))
+            .collect()
+    }
+}
+
+fn softmax(mut logits: Vec<f32>) -> Vec<f32> {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let mut total = 0.0;
+    for logit in &mut logits {
+        *logit = (*logit - max).exp();
+        total += *logit;
+    }
+    for p in &mut logits {
+        *p /= total;
+    }
+    logits
+}
+
+/// PUCT search guided by a policy/value network.
+pub struct NetworkAI {
+    pub search: MctsAI,
+    network: Arc<dyn Evaluator>,
+}
+
+impl NetworkAI {
+    pub fn new(network: Arc<dyn Evaluator>, config: MctsConfig, seed: u64) -> Self {
+        NetworkAI {
+            search: MctsAI::with_network(config, Arc::clone(&network), seed),
+            network,
+        }
+    }
+
+    /// Search over weights saved by `Network::save`, which must be built for
+    /// `board_size` boards.
+    pub fn load(
+        path: impl AsRef<Path>,
+        board_size: usize,
+        config: MctsConfig,
+        seed: u64,
+    ) -> Result<Self, NetworkError> {
+        let network = Network::load(path)?;
+        let expected = network.architecture().board_size;
+        if expected != board_size {
+            return Err(NetworkError::BoardSize {
+                expected,
+                found: board_size,
+            });
+        }
+        Ok(Self::new(Arc::new(network), config, seed))
+    }
+}
+
+impl GoAI for NetworkAI {
+    fn select_move(&mut self, board: &Board, player: Stone) -> Option<Position> {
+        self.search.select_move(board, player)
+    }
+
+    /// The raw policy, without search.
+    fn move_probabilities(&self, board: &Board, player: Stone) -> Option<Vec<f32>> {
+        let mut policy = self.network.evaluate(&[(board, player)]).pop()?.policy;
+        policy.truncate(board.size() * board.size());
+        Some(policy)
+    }
+
+    fn ponder(&mut self, board: &Board, player: Stone) {
+        self.search.ponder(board, player);
+    }
+
+    fn stop_pondering(&mut self) {
+        self.search.stop_pondering();
+    }
+
+    fn is_pondering(&self) -> bool {
+        self.search.is_pondering()
+    }
+}
+
+fn read_array<const N: usize>(input: &mut impl Read) -> std::io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
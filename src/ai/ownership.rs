@@ -0,0 +1,176 @@
+use crate::ai::playout::{self, PlayoutPolicy};
+use crate::core::seed;
+use crate::core::{Board, Position, Stone};
+
+/// Maps every point of the board to a value in [-1, 1], positive for Black.
+/// Values are indexed with `Board::pos_to_index`.
+pub trait OwnershipEstimator {
+    fn estimate(&self, board: &Board, to_play: Stone) -> Vec<f32>;
+}
+
+/// Bouzy's 5/21 algorithm: morphological dilations followed by erosions of
+/// the stones' influence. Values are scaled by the most influence the
+/// dilations can build up, so they compare across positions; stones are
+/// always ±1.
+pub struct InfluenceEstimator {
+    pub dilations: usize,
+    pub erosions: usize,
+}
+
+impl Default for InfluenceEstimator {
+    fn default() -> Self {
+        Self {
+            dilations: 5,
+            erosions: 21,
+        }
+    }
+}
+
+impl OwnershipEstimator for InfluenceEstimator {
+    fn estimate(&self, board: &Board, _to_play: Stone) -> Vec<f32> {
+        let size = board.size();
+        let positions: Vec<Position> = (0..size * size)
+            .map(|i| Position {
+                x: i % size,
+                y: i / size,
+            })
+            .collect();
+
+        let mut values: Vec<i32> = positions
+            .iter()
+            .map(|&p| match board.get_stone(p).ok().flatten() {
+                Some(Stone::Black) => 128,
+                Some(Stone::White) => -128,
+                None => 0,
+            })
+            .collect();
+
+        for _ in 0..self.dilations {
+            let previous = values.clone();
+            for (i, &pos) in positions.iter().enumerate() {
+                let neighbors: Vec<i32> = board
+                    .get_neighbors(pos)
+                    .iter()
+                    .map(|&n| previous[board.pos_to_index(n)])
+                    .collect();
+                if previous[i] >= 0 && neighbors.iter().all(|&v| v >= 0) {
+                    values[i] += neighbors.iter().filter(|&&v| v > 0).count() as i32;
+                }
+                if previous[i] <= 0 && neighbors.iter().all(|&v| v <= 0) {
+                    values[i] -= neighbors.iter().filter(|&&v| v < 0).count() as i32;
+                }
+            }
+        }
+
+        for _ in 0..self.erosions {
+            let previous = values.clone();
+            for (i, &pos) in positions.iter().enumerate() {
+                let neighbors = board
+                    .get_neighbors(pos)
+                    .into_iter()
+                    .map(|n| previous[board.pos_to_index(n)]);
+                if previous[i] > 0 {
+                    let weaker = neighbors.filter(|&v| v <= 0).count() as i32;
+                    values[i] = (previous[i] - weaker).max(0);
+                } else if previous[i] < 0 {
+                    let weaker = neighbors.filter(|&v| v >= 0).count() as i32;
+                    values[i] = (previous[i] + weaker).min(0);
+                }
+            }
+        }
+
+        // One fixed scale for every position: the most the dilations can
+        // add to a point. Stones are seeded far above it and saturate.
+        let scale = (self.dilations * 4).max(1) as f32;
+        values
+            .iter()
+            .map(|&v| (v as f32 / scale).clamp(-1.0, 1.0))
+            .collect()
+    }
+}
+
+/// Averages the final ownership of random playouts that never fill their own
+/// real eyes. Playouts are seeded from `seed` and the position, so the same
+/// position always gets the same estimate.
+pub struct MonteCarloEstimator {
+    pub playouts: usize,
+    pub seed: u64,
+}
+
+impl Default for MonteCarloEstimator {
+    fn default() -> Self {
+        Self {
+            playouts: 100,
+            seed: 0,
+        }
+    }
+}
+
+impl OwnershipEstimator for MonteCarloEstimator {
+    fn estimate(&self, board: &Board, to_play: Stone) -> Vec<f32> {
+        let size = board.size();
+        let mut totals = vec![0.0; size * size];
+        let mut rng = seed::rng(seed::derive(self.seed, board.hash()));
+
+        for _ in 0..self.playouts {
+            let mut playout = board.clone();
+            playout::playout(
+                &mut playout,
+                to_play,
+                &PlayoutPolicy::light(),
+                size * size * 3,
+                &mut rng,
+            );
+
+            for (i, total) in totals.iter_mut().enumerate() {
+                *total += area_owner(
+                    &playout,
+                    Position {
+                        x: i % size,
+                        y: i / size,
+                    },
+                );
+            }
+        }
+
+        let playouts = self.playouts.max(1) as f32;
+        totals.iter().map(|t| t / playouts).collect()
+    }
+}
+
+/// Stones whose owner is the opponent with at least `threshold` confidence.
+pub fn dead_stones(board: &Board, ownership: &[f32], threshold: f32) -> Vec<Position> {
+    let size = board.size();
+    (0..size * size)
+        .map(|i| Position {
+            x: i % size,
+            y: i / size,
+        })
+        .filter(|&pos| match board.get_stone(pos).ok().flatten() {
+            Some(Stone::Black) => ownership[board.pos_to_index(pos)] <= -threshold,
+            Some(Stone::White) => ownership[board.pos_to_index(pos)] >= threshold,
+            None => false,
+        })
+        .collect()
+}
+
+pub(crate) fn area_owner(board: &Board, pos: Position) -> f32 {
+    match board.get_stone(pos).ok().flatten() {
+        Some(Stone::Black) => 1.0,
+        Some(Stone::White) => -1.0,
+        None => {
+            let neighbors: Vec<Option<Stone>> = board
+                .get_neighbors(pos)
+                .iter()
+                .map(|&n| board.get_stone(n).ok().flatten())
+                .collect();
+            if neighbors.iter().all(|&s| s == Some(Stone::Black)) {
+                1.0
+            } else if neighbors.iter().all(|&s| s == Some(Stone::White)) {
+                -1.0
+            } else {
+                0.0
+            }
+        }
+    }
+}
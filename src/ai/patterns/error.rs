@@ -0,0 +1,17 @@
+use crate::sgf::SgfError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PatternError {
+    #[error("Not a pattern file")]
+    BadMagic,
+
+    #[error("Unsupported pattern file version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Failed to read training game: {0}")]
+    Sgf(#[from] SgfError),
+
+    #[error("Failed to access pattern file: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,147 @@
+use super::{PatternError, PatternPriors, pattern_keys};
+use crate::core::game::Move;
+use crate::core::{Board, Position, Stone};
+use crate::sgf::{GameTree, sgf_files};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Virtual wins added to both sides of every MM update, pulling rarely seen
+/// patterns towards 1 instead of letting them collapse to 0.
+const PRIOR_GAMES: f64 = 0.1;
+
+/// Feature groups: the square pattern and the diamond pattern of a point.
+const GROUPS: usize = 2;
+
+/// One move decision: the distinct feature pairs among the empty points,
+/// with how many points share each pair. The played pair is counted in
+/// `PatternLearner::wins`.
+#[derive(Debug, Clone)]
+struct Competition {
+    candidates: Vec<([u32; GROUPS], u32)>,
+}
+
+/// Collects move decisions from game records and fits pattern gammas with
+/// Hunter's minorization-maximization algorithm for the generalized
+/// Bradley-Terry model.
+#[derive(Debug, Clone, Default)]
+pub struct PatternLearner {
+    index: HashMap<u64, u32>,
+    keys: Vec<u64>,
+    /// Feature group of each pattern, indexed like `keys`.
+    groups: Vec<usize>,
+    wins: Vec<f64>,
+    competitions: Vec<Competition>,
+}
+
+impl PatternLearner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn positions(&self) -> usize {
+        self.competitions.len()
+    }
+
+    pub fn patterns(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Records that `player` chose `mv` among the empty points of `board`.
+    pub fn add_position(&mut self, board: &Board, mv: Position, player: Stone) {
+        let size = board.size();
+        let mut counts: HashMap<[u32; GROUPS], u32> = HashMap::new();
+        for i in 0..size * size {
+            let pos = Position {
+                x: i % size,
+                y: i / size,
+            };
+            if board.get_stone(pos).ok() == Some(None) && pos != mv {
+                *counts.entry(self.features(board, pos, player)).or_default() += 1;
+            }
+        }
+
+        let winner = self.features(board, mv, player);
+        *counts.entry(winner).or_default() += 1;
+        for id in winner {
+            self.wins[id as usize] += 1.0;
+        }
+
+        let mut candidates: Vec<_> = counts.into_iter().collect();
+        candidates.sort_unstable();
+        self.competitions.push(Competition { candidates });
+    }
+
+    /// Adds every move of the main line. Returns the number of positions;
+    /// nothing is added when the record contains an illegal move.
+    pub fn add_game(&mut self, tree: &GameTree) -> Result<usize, PatternError> {
+        let mut positions = Vec::new();
+        tree.replay_main_line(|board, mv| {
+            if let Move::Play(stone, pos) = mv {
+                positions.push((board.clone(), pos, stone));
+            }
+        })?;
+        for (board, pos, stone) in &positions {
+            self.add_position(board, *pos, *stone);
+        }
+        Ok(positions.len())
+    }
+
+    /// Adds every `.sgf` game in `dir`. Unreadable or illegal records are
+    /// skipped; returns `(games added, games skipped)`.
+    pub fn add_directory(&mut self, dir: impl AsRef<Path>) -> Result<(usize, usize), PatternError> {
+        let (mut added, mut skipped) = (0, 0);
+        for path in sgf_files(dir)? {
+            match GameTree::load(&path) {
+                Ok(tree) if self.add_game(&tree).is_ok() => added += 1,
+                _ => skipped += 1,
+            }
+        }
+        Ok((added, skipped))
+    }
+
+    /// Runs `iterations` MM sweeps starting from all gammas at 1. Each sweep
+    /// updates one feature group at a time, so that every update sees the
+    /// gammas of the other group as already updated.
+    pub fn train(&self, iterations: usize) -> PatternPriors {
+        let mut gammas = vec![1.0; self.keys.len()];
+
+        for _ in 0..iterations {
+            for group in 0..GROUPS {
+                let mut denominators = vec![PRIOR_GAMES; gammas.len()];
+                for competition in &self.competitions {
+                    let strength = |features: &[u32; GROUPS]| -> f64 {
+                        features.iter().map(|&id| gammas[id as usize]).product()
+                    };
+                    let total: f64 = competition
+                        .candidates
+                        .iter()
+                        .map(|(features, count)| *count as f64 * strength(features))
+                        .sum();
+                    for (features, count) in &competition.candidates {
+                        let id = features[group] as usize;
+                        denominators[id] += *count as f64 * strength(features) / gammas[id] / total;
+                    }
+                }
+                for (i, gamma) in gammas.iter_mut().enumerate() {
+                    if self.groups[i] == group {
+                        *gamma = (self.wins[i] + PRIOR_GAMES) / denominators[i];
+                    }
+                }
+            }
+        }
+
+        PatternPriors::new(self.keys.iter().copied().zip(gammas).collect())
+    }
+
+    fn features(&mut self, board: &Board, pos: Position, player: Stone) -> [u32; GROUPS] {
+        let keys = pattern_keys(board, pos, player);
+        std::array::from_fn(|group| {
+            *self.index.entry(keys[group]).or_insert_with(|| {
+                self.keys.push(keys[group]);
+                self.groups.push(group);
+                self.wins.push(0.0);
+                self.keys.len() as u32 - 1
+            })
+        })
+    }
+}
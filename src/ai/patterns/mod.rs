@@ -0,0 +1,231 @@
+pub mod error;
+pub mod learn;
+
+pub use error::PatternError;
+pub use learn::PatternLearner;
+
+use crate::core::{Board, Position, Stone, Symmetry};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"BGPT";
+const VERSION: u32 = 1;
+
+const SQUARE: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+const DIAMOND: [(i32, i32); 12] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (0, -2),
+    (-2, 0),
+    (2, 0),
+    (0, 2),
+];
+
+/// The neighbourhoods a pattern can cover: the 3x3 square and the radius-2
+/// diamond (the 3x3 square plus the four points two lines away).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatternShape {
+    Square,
+    Diamond,
+}
+
+impl PatternShape {
+    pub const ALL: [PatternShape; 2] = [PatternShape::Square, PatternShape::Diamond];
+
+    fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            PatternShape::Square => &SQUARE,
+            PatternShape::Diamond => &DIAMOND,
+        }
+    }
+
+    fn tag(self) -> u64 {
+        match self {
+            PatternShape::Square => 1 << 60,
+            PatternShape::Diamond => 2 << 60,
+        }
+    }
+}
+
+/// Key of the pattern around `pos` from `player`'s point of view. Each point
+/// is encoded in three bits (empty, off board, or own/opponent stone with
+/// one, two or more liberties), and the key is the smallest encoding over
+/// the eight board symmetries, so rotated and mirrored shapes share a key.
+pub fn pattern_key(board: &Board, pos: Position, player: Stone, shape: PatternShape) -> u64 {
+    let mut liberties = HashMap::new();
+    let offsets = shape.offsets();
+    let states: Vec<u64> = offsets
+        .iter()
+        .map(|&(dx, dy)| point_state(board, pos, dx, dy, player, &mut liberties))
+        .collect();
+
+    let canonical = Symmetry::ALL
+        .into_iter()
+        .map(|symmetry| {
+            offsets.iter().fold(0u64, |code, &offset| {
+                let (dx, dy) = symmetry.apply_offset(offset);
+                let source = offsets
+                    .iter()
+                    .position(|&o| o == (dx, dy))
+                    .expect("pattern offsets are closed under symmetry");
+                (code << 3) | states[source]
+            })
+        })
+        .min()
+        .unwrap_or_default();
+
+    shape.tag() | canonical
+}
+
+/// Keys of every shape around `pos`, in `PatternShape::ALL` order.
+pub fn pattern_keys(board: &Board, pos: Position, player: Stone) -> [u64; 2] {
+    PatternShape::ALL.map(|shape| pattern_key(board, pos, player, shape))
+}
+
+fn point_state(
+    board: &Board,
+    pos: Position,
+    dx: i32,
+    dy: i32,
+    player: Stone,
+    liberties: &mut HashMap<usize, usize>,
+) -> u64 {
+    let x = pos.x as i32 + dx;
+    let y = pos.y as i32 + dy;
+    let size = board.size() as i32;
+    if !(0..size).contains(&x) || !(0..size).contains(&y) {
+        return 1;
+    }
+
+    let point = Position {
+        x: x as usize,
+        y: y as usize,
+    };
+    let Some(stone) = board.get_stone(point).ok().flatten() else {
+        return 0;
+    };
+
+    let count = *liberties
+        .entry(board.pos_to_index(point))
+        .or_insert_with(|| board.count_liberties(&board.get_group(point)));
+    let base = if stone == player { 2 } else { 5 };
+    base + count.clamp(1, 3) as u64 - 1
+}
+
+/// Bradley-Terry strengths ("gammas") of patterns. A move's strength is the
+/// product of the gammas of its patterns; unseen patterns count as 1.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PatternPriors {
+    gammas: HashMap<u64, f64>,
+}
+
+impl PatternPriors {
+    pub fn new(gammas: HashMap<u64, f64>) -> Self {
+        PatternPriors { gammas }
+    }
+
+    pub fn len(&self) -> usize {
+        self.gammas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gammas.is_empty()
+    }
+
+    pub fn gamma(&self, key: u64) -> f64 {
+        self.gammas.get(&key).copied().unwrap_or(1.0)
+    }
+
+    pub fn strength(&self, board: &Board, pos: Position, player: Stone) -> f64 {
+        pattern_keys(board, pos, player)
+            .iter()
+            .map(|&key| self.gamma(key))
+            .product()
+    }
+
+    /// Move probabilities over the board, indexed with `Board::pos_to_index`.
+    /// Illegal points get zero.
+    pub fn move_priors(&self, board: &Board, player: Stone) -> Vec<f32> {
+        let size = board.size();
+        let mut priors: Vec<f64> = (0..size * size)
+            .map(|i| {
+                let pos = Position {
+                    x: i % size,
+                    y: i / size,
+                };
+                if board.is_valid_move(pos, player) {
+                    self.strength(board, pos, player)
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        let total: f64 = priors.iter().sum();
+        if total > 0.0 {
+            priors.iter_mut().for_each(|p| *p /= total);
+        }
+        priors.into_iter().map(|p| p as f32).collect()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PatternError> {
+        let mut entries: Vec<_> = self.gammas.iter().collect();
+        entries.sort_by_key(|&(&key, _)| key);
+
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&(entries.len() as u64).to_le_bytes())?;
+        for (key, gamma) in entries {
+            out.write_all(&key.to_le_bytes())?;
+            out.write_all(&gamma.to_le_bytes())?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PatternError> {
+        let mut input = std::io::BufReader::new(std::fs::File::open(path)?);
+
+        let mut magic = [0; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(PatternError::BadMagic);
+        }
+        let version = u32::from_le_bytes(read_array(&mut input)?);
+        if version != VERSION {
+            return Err(PatternError::UnsupportedVersion(version));
+        }
+
+        let count = u64::from_le_bytes(read_array(&mut input)?);
+        let mut gammas = HashMap::new();
+        for _ in 0..count {
+            let key = u64::from_le_bytes(read_array(&mut input)?);
+            let gamma = f64::from_le_bytes(read_array(&mut input)?);
+            gammas.insert(key, gamma);
+        }
+        Ok(PatternPriors { gammas })
+    }
+}
+
+fn read_array<const N: usize>(input: &mut impl Read) -> std::io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
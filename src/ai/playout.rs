@@ -0,0 +1,245 @@
+use crate::config::{ConfigError, ConfigFile};
+use crate::core::{Board, Position, Stone};
+use crate::tactics::eye::{self, EyeKind};
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+/// Move selection rules for random playouts. `uniform` is a plain random
+/// player, `light` is the usual cheap MCTS playout and `heavy` adds the
+/// tactical answers most Go programs use.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayoutPolicy {
+    pub avoid_eye_filling: bool,
+    pub answer_atari: bool,
+    pub prefer_captures: bool,
+    /// Pass only when no move is left after the other filters; otherwise pass
+    /// with `pass_probability`.
+    pub pass_only_when_stuck: bool,
+    pub pass_probability: f64,
+}
+
+impl Default for PlayoutPolicy {
+    fn default() -> Self {
+        PlayoutPolicy::light()
+    }
+}
+
+impl PlayoutPolicy {
+    pub fn uniform(pass_probability: f64) -> Self {
+        PlayoutPolicy {
+            avoid_eye_filling: false,
+            answer_atari: false,
+            prefer_captures: false,
+            pass_only_when_stuck: false,
+            pass_probability,
+        }
+    }
+
+    pub fn light() -> Self {
+        PlayoutPolicy {
+            avoid_eye_filling: true,
+            pass_only_when_stuck: true,
+            ..PlayoutPolicy::uniform(0.0)
+        }
+    }
+
+    pub fn heavy() -> Self {
+        PlayoutPolicy {
+            answer_atari: true,
+            prefer_captures: true,
+            ..PlayoutPolicy::light()
+        }
+    }
+
+    /// Reads `<prefix><field>` keys on top of `self`.
+    pub fn from_config(mut self, config: &ConfigFile, prefix: &str) -> Result<Self, ConfigError> {
+        for (flag, name) in [
+            (&mut self.avoid_eye_filling, "avoid_eye_filling"),
+            (&mut self.answer_atari, "answer_atari"),
+            (&mut self.prefer_captures, "prefer_captures"),
+            (&mut self.pass_only_when_stuck, "pass_only_when_stuck"),
+        ] {
+            if let Some(value) = config.get(&format!("{prefix}{name}"))? {
+                *flag = value;
+            }
+        }
+        if let Some(value) = config.get(&format!("{prefix}pass_probability"))? {
+            self.pass_probability = value;
+        }
+        Ok(self)
+    }
+
+    pub fn write_config(&self, config: &mut ConfigFile, prefix: &str) {
+        config.set(
+            &format!("{prefix}avoid_eye_filling"),
+            self.avoid_eye_filling,
+        );
+        config.set(&format!("{prefix}answer_atari"), self.answer_atari);
+        config.set(&format!("{prefix}prefer_captures"), self.prefer_captures);
+        config.set(
+            &format!("{prefix}pass_only_when_stuck"),
+            self.pass_only_when_stuck,
+        );
+        config.set(&format!("{prefix}pass_probability"), self.pass_probability);
+    }
+
+    pub fn select_move(
+        &self,
+        board: &Board,
+        player: Stone,
+        rng: &mut impl Rng,
+    ) -> Option<Position> {
+        if !self.pass_only_when_stuck && rng.random_bool(self.pass_probability.clamp(0.0, 1.0)) {
+            return None;
+        }
+
+        if self.answer_atari
+            && let Some(pos) = pick(board, player, atari_answers(board, player), rng)
+        {
+            return Some(pos);
+        }
+        if self.prefer_captures
+            && let Some(pos) = pick(board, player, captures(board, player.opposite()), rng)
+        {
+            return Some(pos);
+        }
+
+        let size = board.size();
+        let mut candidates: Vec<Position> = (0..size * size)
+            .map(|i| Position {
+                x: i % size,
+                y: i / size,
+            })
+            .filter(|&pos| board.get_stone(pos).ok() == Some(None))
+            .collect();
+        candidates.shuffle(rng);
+
+        candidates.into_iter().find(|&pos| {
+            !(self.avoid_eye_filling && eye::classify(board, pos, player) == EyeKind::Real)
+                && board.is_valid_move(pos, player)
+        })
+    }
+}
+
+/// Plays `policy` against itself from `board` until both sides pass or
+/// `max_moves` moves have been made. Returns the number of moves played.
+pub fn playout(
+    board: &mut Board,
+    to_play: Stone,
+    policy: &PlayoutPolicy,
+    max_moves: usize,
+    rng: &mut impl Rng,
+) -> usize {
+    playout_with(board, to_play, policy, max_moves, rng, |_, _| {})
+}
+
+/// `playout` that reports every stone played to `record`.
+pub fn playout_with(
+    board: &mut Board,
+    to_play: Stone,
+    policy: &PlayoutPolicy,
+    max_moves: usize,
+    rng: &mut impl Rng,
+    mut record: impl FnMut(Stone, Position),
+) -> usize {
+    let mut player = to_play;
+    let mut passes = 0;
+    let mut moves = 0;
+
+    while moves < max_moves && passes < 2 {
+        match policy.select_move(board, player, rng) {
+            Some(pos) if board.play(pos, player).is_ok() => {
+                record(player, pos);
+                passes = 0;
+            }
+            _ => {
+                board.pass();
+                passes += 1;
+            }
+        }
+        moves += 1;
+        player = player.opposite();
+    }
+    moves
+}
+
+/// Shuffles `moves` and returns the first legal one.
+fn pick(
+    board: &Board,
+    player: Stone,
+    mut moves: Vec<Position>,
+    rng: &mut impl Rng,
+) -> Option<Position> {
+    moves.shuffle(rng);
+    moves
+        .into_iter()
+        .find(|&pos| board.is_valid_move(pos, player))
+}
+
+/// Liberties of `color` groups in atari, i.e. the points that capture them.
+fn captures(board: &Board, color: Stone) -> Vec<Position> {
+    let mut moves = Vec::new();
+    for group in groups(board, color) {
+        let liberties = board.liberties(&group);
+        if liberties.len() == 1 && !moves.contains(&liberties[0]) {
+            moves.push(liberties[0]);
+        }
+    }
+    moves
+}
+
+/// Moves that rescue a `player` group in atari: capturing an adjacent
+/// attacker, or extending when that gains liberties.
+fn atari_answers(board: &Board, player: Stone) -> Vec<Position> {
+    let mut moves = Vec::new();
+
+    for group in groups(board, player) {
+        let liberties = board.liberties(&group);
+        if liberties.len() != 1 {
+            continue;
+        }
+
+        for &stone in &group {
+            for neighbor in board.get_neighbors(stone) {
+                if board.get_stone(neighbor).ok().flatten() == Some(player.opposite()) {
+                    let attacker = board.liberties(&board.get_group(neighbor));
+                    if attacker.len() == 1 && !moves.contains(&attacker[0]) {
+                        moves.push(attacker[0]);
+                    }
+                }
+            }
+        }
+
+        let escape = liberties[0];
+        let mut after = board.clone();
+        if after.play(escape, player).is_ok()
+            && after.count_liberties(&after.get_group(escape)) >= 2
+            && !moves.contains(&escape)
+        {
+            moves.push(escape);
+        }
+    }
+    moves
+}
+
+fn groups(board: &Board, color: Stone) -> Vec<Vec<Position>> {
+    let size = board.size();
+    let mut seen = vec![false; size * size];
+    let mut groups = Vec::new();
+
+    for i in 0..size * size {
+        let pos = Position {
+            x: i % size,
+            y: i / size,
+        };
+        if seen[i] || board.get_stone(pos).ok().flatten() != Some(color) {
+            continue;
+        }
+        let group = board.get_group(pos);
+        for &stone in &group {
+            seen[board.pos_to_index(stone)] = true;
+        }
+        groups.push(group);
+    }
+    groups
+}
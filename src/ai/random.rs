@@ -1,30 +1,33 @@
 use super::GoAI;
+use super::playout::PlayoutPolicy;
+use crate::core::seed;
 use crate::core::{Board, Position, Stone};
-use rand::random_range;
+use rand::rngs::StdRng;
 
-#[derive(Default)]
-pub struct RandomAI {}
+pub struct RandomAI {
+    pub policy: PlayoutPolicy,
+    rng: StdRng,
+}
 
-impl GoAI for RandomAI {
-    fn select_move(&self, board: &Board, player: Stone) -> Option<Position> {
-        let opp = rand::random_range(0..101);
-        if opp < 10 {
-            return None;
+impl RandomAI {
+    pub fn new(policy: PlayoutPolicy, seed: u64) -> Self {
+        RandomAI {
+            policy,
+            rng: seed::rng(seed),
         }
-        let mut valid_moves = Vec::new();
+    }
+}
 
-        for x in 0..board.size() {
-            for y in 0..board.size() {
-                let pos = Position { x, y };
-                if board.is_valid_move(pos, player) {
-                    valid_moves.push(pos);
-                }
-            }
-        }
-        if valid_moves.is_empty() {
-            None
-        } else {
-            Some(valid_moves[rand::random_range(0..valid_moves.len())])
-        }
+/// Seeded with 0 like every other default, so that it replays the same
+/// moves; use `new` for another stream.
+impl Default for RandomAI {
+    fn default() -> Self {
+        RandomAI::new(PlayoutPolicy::default(), 0)
+    }
+}
+
+impl GoAI for RandomAI {
+    fn select_move(&mut self, board: &Board, player: Stone) -> Option<Position> {
+        self.policy.select_move(board, player, &mut self.rng)
     }
 }
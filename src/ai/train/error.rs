@@ -0,0 +1,46 @@
+use crate::core::GoError;
+use crate::sgf::SgfError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TrainError {
+    #[error("Not a training shard")]
+    BadMagic,
+
+    #[error("Unsupported training shard version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Shard holds {found} input planes, this build encodes {expected}")]
+    PlaneMismatch { expected: usize, found: usize },
+
+    #[error("Shard declares an invalid board size of {0}")]
+    InvalidBoardSize(usize),
+
+    #[error("Game is played on {found}x{found}, the export is {expected}x{expected}")]
+    BoardSize { expected: usize, found: usize },
+
+    #[error("Sample holds {found} feature values, the shard expects {expected}")]
+    SampleSize { expected: usize, found: usize },
+
+    #[error("Setup stones other than Black's handicap are not supported")]
+    Setup,
+
+    #[error("Handicap stones cannot be placed: {0}")]
+    Handicap(#[source] GoError),
+
+    #[error("Move {number} is played out of turn")]
+    OutOfTurn { number: usize },
+
+    #[error("Move {number} is illegal: {source}")]
+    IllegalMove {
+        number: usize,
+        #[source]
+        source: GoError,
+    },
+
+    #[error("Failed to read training game: {0}")]
+    Sgf(#[from] SgfError),
+
+    #[error("Failed to access training data: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,332 @@
+pub mod error;
+
+pub use error::TrainError;
+
+use crate::ai::features::{self, PLANES};
+use crate::core::game::Game;
+use crate::core::seed;
+use crate::core::{Board, Position, Rules, Stone, Symmetry};
+use crate::sgf::{GameTree, sgf_files};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"BGTD";
+const VERSION: u32 = 1;
+const MAX_BOARD_SIZE: usize = 25;
+
+/// One position of a game record, seen from the side to move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// Planes laid out as `features::encode` does.
+    pub features: Vec<f32>,
+    /// `Board::pos_to_index` of the move played; `size * size` for a pass.
+    pub mv: usize,
+    /// 1 when the side to move went on to win, -1 when it lost and 0 when
+    /// the record has no result.
+    pub outcome: f32,
+}
+
+/// Turns game records of one board size into training samples, written in
+/// shards of shuffled samples. Shuffling stays within a shard, so trainers
+/// should still draw from several shards at once.
+pub struct TrainingExporter {
+    /// Samples per shard file.
+    pub shard_size: usize,
+    /// Adds every position in all eight orientations instead of the
+    /// record's only.
+    pub augment: bool,
+    board_size: usize,
+    output: PathBuf,
+    rng: StdRng,
+    pending: Vec<Sample>,
+    shards: Vec<PathBuf>,
+    samples: usize,
+}
+
+impl TrainingExporter {
+    pub fn new(board_size: usize, output: impl Into<PathBuf>, seed: u64) -> Self {
+        TrainingExporter {
+            shard_size: 4096,
+            augment: true,
+            board_size,
+            output: output.into(),
+            rng: seed::rng(seed),
+            pending: Vec::new(),
+            shards: Vec::new(),
+            samples: 0,
+        }
+    }
+
+    pub fn board_size(&self) -> usize {
+        self.board_size
+    }
+
+    /// Samples added so far, written or not.
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    pub fn shards(&self) -> &[PathBuf] {
+        &self.shards
+    }
+
+    /// Samples for every main-line move of `tree`, after replaying the whole
+    /// record through `Game` to check that it is legal.
+    pub fn game_samples(&self, tree: &GameTree) -> Result<Vec<Sample>, TrainError> {
+        if tree.size != self.board_size {
+            return Err(TrainError::BoardSize {
+                expected: self.board_size,
+                found: tree.size,
+            });
+        }
+
+        let winner = tree.winner();
+        let area = tree.size * tree.size;
+        let mut samples = Vec::new();
+        for (board, player, mv) in replay(tree)? {
+            let sample = Sample {
+                features: features::encode(&board, player),
+                mv: mv.map_or(area, |pos| board.pos_to_index(pos)),
+                outcome: match winner {
+                    Some(winner) if winner == player => 1.0,
+                    Some(_) => -1.0,
+                    None => 0.0,
+                },
+            };
+            if self.augment {
+                samples.extend(
+                    Symmetry::ALL
+                        .into_iter()
+                        .map(|symmetry| transform(&sample, symmetry, tree.size)),
+                );
+            } else {
+                samples.push(sample);
+            }
+        }
+        Ok(samples)
+    }
+
+    /// Adds the samples of one game and writes every shard that fills up.
+    /// Nothing is added when the record is broken. Returns the number of
+    /// samples added.
+    pub fn add_game(&mut self, tree: &GameTree) -> Result<usize, TrainError> {
+        let samples = self.game_samples(tree)?;
+        let added = samples.len();
+        self.samples += added;
+        self.pending.extend(samples);
+        while self.pending.len() >= self.shard_size.max(1) {
+            self.write_pending(self.shard_size.max(1))?;
+        }
+        Ok(added)
+    }
+
+    /// Adds every `.sgf` game in `dir`, skipping unreadable, illegal or
+    /// differently sized records. Returns `(games added, games skipped)`.
+    pub fn add_directory(&mut self, dir: impl AsRef<Path>) -> Result<(usize, usize), TrainError> {
+        let (mut added, mut skipped) = (0, 0);
+        for path in sgf_files(dir)? {
+            let tree = match GameTree::load(&path) {
+                Ok(tree) => tree,
+                Err(_) => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+            match self.add_game(&tree) {
+                Ok(_) => added += 1,
+                Err(TrainError::Io(e)) => return Err(TrainError::Io(e)),
+                Err(_) => skipped += 1,
+            }
+        }
+        Ok((added, skipped))
+    }
+
+    /// Writes the last, partly filled shard and returns every shard written.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>, TrainError> {
+        if !self.pending.is_empty() {
+            self.write_pending(self.pending.len())?;
+        }
+        Ok(self.shards)
+    }
+
+    fn write_pending(&mut self, count: usize) -> Result<(), TrainError> {
+        let mut shard: Vec<Sample> = self.pending.drain(..count).collect();
+        shard.shuffle(&mut self.rng);
+
+        std::fs::create_dir_all(&self.output)?;
+        let path = self
+            .output
+            .join(format!("shard-{:05}.bin", self.shards.len()));
+        write_shard(&path, self.board_size, &shard)?;
+        self.shards.push(path);
+        Ok(())
+    }
+}
+
+/// Writes `samples` as one shard. The layout, little endian, is the magic,
+/// version, board size, input planes and sample count as `u32`, then per
+/// sample the feature planes packed one bit per value (lowest bit first,
+/// padded to whole bytes), the move index as `u16` and the outcome as `i8`.
+pub fn write_shard(
+    path: impl AsRef<Path>,
+    board_size: usize,
+    samples: &[Sample],
+) -> Result<(), TrainError> {
+    let values = PLANES * board_size * board_size;
+    if let Some(sample) = samples.iter().find(|s| s.features.len() != values) {
+        return Err(TrainError::SampleSize {
+            expected: values,
+            found: sample.features.len(),
+        });
+    }
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    out.write_all(MAGIC)?;
+    out.write_all(&VERSION.to_le_bytes())?;
+    out.write_all(&(board_size as u32).to_le_bytes())?;
+    out.write_all(&(PLANES as u32).to_le_bytes())?;
+    out.write_all(&(samples.len() as u32).to_le_bytes())?;
+
+    let mut bits = vec![0u8; values.div_ceil(8)];
+    for sample in samples {
+        bits.fill(0);
+        for (i, &value) in sample.features.iter().enumerate() {
+            if value != 0.0 {
+                bits[i / 8] |= 1 << (i % 8);
+            }
+        }
+        out.write_all(&bits)?;
+        out.write_all(&(sample.mv as u16).to_le_bytes())?;
+        out.write_all(&[sample.outcome as i8 as u8])?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Reads a shard written by `write_shard`. Returns the board size and the
+/// samples.
+pub fn read_shard(path: impl AsRef<Path>) -> Result<(usize, Vec<Sample>), TrainError> {
+    let mut input = std::io::BufReader::new(std::fs::File::open(path)?);
+
+    let mut magic = [0; 4];
+    input.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(TrainError::BadMagic);
+    }
+    let version = u32::from_le_bytes(read_array(&mut input)?);
+    if version != VERSION {
+        return Err(TrainError::UnsupportedVersion(version));
+    }
+    let board_size = u32::from_le_bytes(read_array(&mut input)?) as usize;
+    if !(1..=MAX_BOARD_SIZE).contains(&board_size) {
+        return Err(TrainError::InvalidBoardSize(board_size));
+    }
+    let planes = u32::from_le_bytes(read_array(&mut input)?) as usize;
+    if planes != PLANES {
+        return Err(TrainError::PlaneMismatch {
+            expected: PLANES,
+            found: planes,
+        });
+    }
+    let count = u32::from_le_bytes(read_array(&mut input)?);
+
+    let values = board_size
+        .checked_mul(board_size)
+        .and_then(|area| area.checked_mul(PLANES))
+        .ok_or(TrainError::InvalidBoardSize(board_size))?;
+    let mut bits = vec![0u8; values.div_ceil(8)];
+    // The count is not trusted for the allocation; a truncated shard fails
+    // on the first missing sample instead.
+    let mut samples = Vec::new();
+    for _ in 0..count {
+        input.read_exact(&mut bits)?;
+        let features = (0..values)
+            .map(|i| ((bits[i / 8] >> (i % 8)) & 1) as f32)
+            .collect();
+        let mv = u16::from_le_bytes(read_array(&mut input)?) as usize;
+        let [outcome] = read_array(&mut input)?;
+        samples.push(Sample {
+            features,
+            mv,
+            outcome: outcome as i8 as f32,
+        });
+    }
+    Ok((board_size, samples))
+}
+
+/// The position and side to move before each main-line move, with the move
+/// played. Only Black setup stones on the root, i.e. handicap, are allowed.
+fn replay(tree: &GameTree) -> Result<Vec<(Board, Stone, Option<Position>)>, TrainError> {
+    let root = tree.node(tree.root());
+    if !root.add_white.is_empty() {
+        return Err(TrainError::Setup);
+    }
+    let mut game = Game::with_handicap_stones(
+        tree.size,
+        Rules::default(),
+        tree.komi,
+        root.add_black.clone(),
+    )
+    .map_err(TrainError::Handicap)?;
+
+    let mut positions = Vec::new();
+    for id in tree.main_line().into_iter().skip(1) {
+        let node = tree.node(id);
+        if !node.add_black.is_empty() || !node.add_white.is_empty() {
+            return Err(TrainError::Setup);
+        }
+        let Some(mv) = node.mv else {
+            continue;
+        };
+        let number = positions.len() + 1;
+        if mv.stone() != game.current_player() {
+            return Err(TrainError::OutOfTurn { number });
+        }
+        positions.push((game.board_state().clone(), mv.stone(), mv.position()));
+        match mv.position() {
+            Some(pos) => {
+                game.make_move(pos)
+                    .map_err(|source| TrainError::IllegalMove { number, source })?;
+            }
+            None => game.pass(),
+        }
+    }
+    Ok(positions)
+}
+
+/// `sample` with every plane and the move moved by `symmetry`.
+fn transform(sample: &Sample, symmetry: Symmetry, size: usize) -> Sample {
+    let area = size * size;
+    let map = |index: usize| {
+        if index == area {
+            return area;
+        }
+        let pos = symmetry.apply(
+            Position {
+                x: index % size,
+                y: index / size,
+            },
+            size,
+        );
+        pos.y * size + pos.x
+    };
+
+    let mut features = vec![0.0; sample.features.len()];
+    for (plane, values) in sample.features.chunks_exact(area).enumerate() {
+        for (index, &value) in values.iter().enumerate() {
+            features[plane * area + map(index)] = value;
+        }
+    }
+    Sample {
+        features,
+        mv: map(sample.mv),
+        outcome: sample.outcome,
+    }
+}
+
+fn read_array<const N: usize>(input: &mut impl Read) -> std::io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
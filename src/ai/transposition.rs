@@ -0,0 +1,204 @@
+use crate::core::Position;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+/// Statistics of one move out of a position. `wins` are counted for the
+/// player making the move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edge {
+    /// `None` is a pass.
+    pub mv: Option<Position>,
+    pub visits: u32,
+    pub wins: f32,
+    /// All-moves-as-first statistics: simulations through the parent in
+    /// which the mover played this point later on.
+    pub amaf_visits: u32,
+    pub amaf_wins: f32,
+    /// Network policy for the move; PUCT search only.
+    pub policy: f32,
+    /// `policy` with the root noise of the current search mixed in.
+    pub prior: f32,
+}
+
+impl Edge {
+    pub fn new(mv: Option<Position>) -> Self {
+        Edge {
+            mv,
+            visits: 0,
+            wins: 0.0,
+            amaf_visits: 0,
+            amaf_wins: 0.0,
+            policy: 0.0,
+            prior: 0.0,
+        }
+    }
+}
+
+/// A searched position. Positions reached by different move orders share
+/// one node, so the search graph is a DAG rather than a tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub key: u64,
+    pub generation: u32,
+    pub visits: u32,
+    pub edges: Vec<Edge>,
+}
+
+/// Which entry survives when two positions compete for a bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Replacement {
+    /// The new position always takes the less visited slot, except the
+    /// slot of the pinned root.
+    Always,
+    /// Well-explored positions of the current search are kept; the new
+    /// position is dropped when both slots hold more visits than it will.
+    #[default]
+    PreferVisited,
+}
+
+/// Fixed-size, thread-safe store of search nodes. Each key maps to a bucket
+/// of two slots, every slot behind its own lock.
+pub struct TranspositionTable {
+    slots: Vec<Mutex<Option<Node>>>,
+    replacement: Replacement,
+    generation: AtomicU32,
+    /// Key of the search root, which is never replaced.
+    pinned: AtomicU64,
+    len: AtomicUsize,
+}
+
+impl TranspositionTable {
+    pub fn new(capacity: usize, replacement: Replacement) -> Self {
+        let capacity = capacity.max(2).next_multiple_of(2);
+        TranspositionTable {
+            slots: (0..capacity).map(|_| Mutex::new(None)).collect(),
+            replacement,
+            generation: AtomicU32::new(0),
+            pinned: AtomicU64::new(0),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// A table that stays within roughly `bytes` when nodes have about
+    /// `edges` moves each.
+    pub fn with_memory(bytes: usize, edges: usize, replacement: Replacement) -> Self {
+        Self::new(bytes / Self::entry_size(edges), replacement)
+    }
+
+    fn entry_size(edges: usize) -> usize {
+        size_of::<Mutex<Option<Node>>>() + edges * size_of::<Edge>()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Starts a new search; nodes of earlier searches become preferred
+    /// victims but stay usable until replaced.
+    pub fn new_generation(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Keeps the node for `key` from being replaced while it is the root.
+    pub fn pin(&self, key: u64) {
+        self.pinned.store(key, Ordering::Relaxed);
+    }
+
+    /// Approximate bytes held by the stored nodes.
+    pub fn memory_usage(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|slot| match &*lock(slot) {
+                Some(node) => Self::entry_size(node.edges.capacity()),
+                None => size_of::<Mutex<Option<Node>>>(),
+            })
+            .sum()
+    }
+
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            *lock(slot) = None;
+        }
+        self.len.store(0, Ordering::Relaxed);
+    }
+
+    /// Runs `f` on the node for `key` with its slot locked, or returns
+    /// `None` when the position is not stored.
+    pub fn with<R>(&self, key: u64, f: impl FnOnce(&mut Node) -> R) -> Option<R> {
+        for slot in self.bucket(key) {
+            let mut guard = lock(slot);
+            if let Some(node) = guard.as_mut()
+                && node.key == key
+            {
+                return Some(f(node));
+            }
+        }
+        None
+    }
+
+    /// Stores `node` unless the replacement policy keeps the current
+    /// entries. Returns whether it was stored. An existing node for the same
+    /// key is left alone.
+    pub fn insert(&self, mut node: Node) -> bool {
+        let generation = self.generation();
+        node.generation = generation;
+        node.edges.shrink_to_fit();
+
+        let [first, second] = self.bucket(node.key);
+        let mut first = lock(first);
+        let mut second = lock(second);
+        for slot in [&*first, &*second] {
+            if slot.as_ref().is_some_and(|n| n.key == node.key) {
+                return false;
+            }
+        }
+
+        let pinned = self.pinned.load(Ordering::Relaxed);
+        let worth = |slot: &Option<Node>| match slot {
+            None => (false, false, 0),
+            Some(n) => (n.key == pinned, n.generation == generation, n.visits),
+        };
+        let victim = if worth(&first) <= worth(&second) {
+            &mut *first
+        } else {
+            &mut *second
+        };
+
+        match victim {
+            None => self.len.fetch_add(1, Ordering::Relaxed),
+            Some(old) => {
+                if self.replacement == Replacement::PreferVisited
+                    && old.generation == generation
+                    && old.visits > node.visits
+                {
+                    return false;
+                }
+                0
+            }
+        };
+        *victim = Some(node);
+        true
+    }
+
+    fn bucket(&self, key: u64) -> [&Mutex<Option<Node>>; 2] {
+        let index = (key % self.slots.len() as u64) as usize & !1;
+        [&self.slots[index], &self.slots[index + 1]]
+    }
+}
+
+/// Locks a slot, recovering it if a searching thread panicked.
+fn lock(slot: &Mutex<Option<Node>>) -> std::sync::MutexGuard<'_, Option<Node>> {
+    slot.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,213 @@
+use crate::ai::GoAI;
+use crate::ai::heuristic::HeuristicAI;
+use crate::core::{Position, Rules, Stone, game::Game};
+use rand::Rng;
+
+/// Conditions shared by every game of a match.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchSettings {
+    pub board_size: usize,
+    pub komi: f32,
+    pub rules: Rules,
+    /// Random moves played before the engines take over, so that two
+    /// deterministic engines do not replay the same game.
+    pub opening_moves: usize,
+    pub max_moves: usize,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        MatchSettings {
+            board_size: 9,
+            komi: 6.5,
+            rules: Rules::Chinese,
+            opening_moves: 4,
+            max_moves: 200,
+        }
+    }
+}
+
+/// Plays one game to the end, or to `max_moves`, and returns it for scoring
+/// or recording.
+pub fn play_game(
+    black: &mut dyn GoAI,
+    white: &mut dyn GoAI,
+    settings: &MatchSettings,
+    rng: &mut impl Rng,
+) -> Game {
+    let mut game = Game::with_rules(settings.board_size, settings.rules, settings.komi);
+
+    while game.is_game_over().is_none() && game.moves().len() < settings.max_moves {
+        let player = game.current_player();
+        let choice = if game.moves().len() < settings.opening_moves {
+            random_move(&game, player, rng)
+        } else {
+            match player {
+                Stone::Black => black.select_move(game.board_state(), player),
+                Stone::White => white.select_move(game.board_state(), player),
+            }
+        };
+
+        match choice {
+            Some(pos) if game.make_move(pos).is_ok() => {}
+            _ => game.pass(),
+        }
+    }
+
+    game
+}
+
+/// Fraction of `games` won by `candidate` against `opponent`, alternating
+/// colours.
+pub fn win_rate(
+    candidate: &mut dyn GoAI,
+    opponent: &mut dyn GoAI,
+    games: usize,
+    settings: &MatchSettings,
+    rng: &mut impl Rng,
+) -> f64 {
+    if games == 0 {
+        return 0.5;
+    }
+
+    let mut wins = 0;
+    for game in 0..games {
+        let candidate_color = if game % 2 == 0 {
+            Stone::Black
+        } else {
+            Stone::White
+        };
+        let winner = match candidate_color {
+            Stone::Black => play_game(candidate, opponent, settings, rng),
+            Stone::White => play_game(opponent, candidate, settings, rng),
+        }
+        .score(&[])
+        .winner();
+        if winner == candidate_color {
+            wins += 1;
+        }
+    }
+    wins as f64 / games as f64
+}
+
+fn random_move(game: &Game, player: Stone, rng: &mut impl Rng) -> Option<Position> {
+    let board = game.board_state();
+    let moves: Vec<Position> = (0..board.size() * board.size())
+        .map(|idx| Position {
+            x: idx % board.size(),
+            y: idx / board.size(),
+        })
+        .filter(|&pos| board.is_valid_move(pos, player))
+        .collect();
+    (!moves.is_empty()).then(|| moves[rng.random_range(0..moves.len())])
+}
+
+/// Simultaneous perturbation stochastic approximation over the
+/// `HeuristicAI` weights. Each iteration plays both perturbed weight sets
+/// against the fixed baseline and steps along the estimated gradient of the
+/// win rate. Perturbations and steps are relative to the starting weights so
+/// that large and small weights move at comparable speed.
+#[derive(Debug, Clone)]
+pub struct Spsa {
+    pub iterations: usize,
+    pub games_per_side: usize,
+    pub evaluation_games: usize,
+    /// Step size numerator `a` of `a / (k + 1 + A)^0.602`.
+    pub step: f64,
+    /// Perturbation numerator `c` of `c / (k + 1)^0.101`.
+    pub perturbation: f64,
+    pub stability: f64,
+    pub matches: MatchSettings,
+}
+
+impl Default for Spsa {
+    fn default() -> Self {
+        Spsa {
+            iterations: 50,
+            games_per_side: 8,
+            evaluation_games: 32,
+            step: 0.5,
+            perturbation: 0.2,
+            stability: 5.0,
+            matches: MatchSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TuningResult {
+    pub best: HeuristicAI,
+    pub best_win_rate: f64,
+    pub history: Vec<f64>,
+}
+
+impl Spsa {
+    pub fn tune(
+        &self,
+        start: HeuristicAI,
+        baseline: &mut dyn GoAI,
+        rng: &mut impl Rng,
+        mut progress: impl FnMut(usize, &HeuristicAI, f64),
+    ) -> TuningResult {
+        let scale = start.weights().map(|w| w.abs().max(0.1));
+        let mut theta = start.weights();
+
+        let mut best = start.clone();
+        let mut best_win_rate = win_rate(
+            &mut best,
+            baseline,
+            self.evaluation_games,
+            &self.matches,
+            rng,
+        );
+        let mut history = vec![best_win_rate];
+
+        for k in 0..self.iterations {
+            let a_k = self.step / (k as f64 + 1.0 + self.stability).powf(0.602);
+            let c_k = self.perturbation / (k as f64 + 1.0).powf(0.101);
+            let delta: [f64; 5] =
+                std::array::from_fn(|_| if rng.random_bool(0.5) { 1.0 } else { -1.0 });
+
+            let mut plus = start.with_weights(std::array::from_fn(|i| {
+                (theta[i] + c_k * delta[i] * scale[i]).max(0.0)
+            }));
+            let mut minus = start.with_weights(std::array::from_fn(|i| {
+                (theta[i] - c_k * delta[i] * scale[i]).max(0.0)
+            }));
+
+            let difference = win_rate(&mut plus, baseline, self.games_per_side, &self.matches, rng)
+                - win_rate(
+                    &mut minus,
+                    baseline,
+                    self.games_per_side,
+                    &self.matches,
+                    rng,
+                );
+            for i in 0..theta.len() {
+                let gradient = difference / (2.0 * c_k * delta[i]);
+                theta[i] = (theta[i] + a_k * gradient * scale[i]).max(0.0);
+            }
+
+            let mut candidate = start.with_weights(theta);
+            let rate = win_rate(
+                &mut candidate,
+                baseline,
+                self.evaluation_games,
+                &self.matches,
+                rng,
+            );
+            history.push(rate);
+            progress(k, &candidate, rate);
+            if rate > best_win_rate {
+                best = candidate;
+                best_win_rate = rate;
+            }
+        }
+
+        TuningResult {
+            best,
+            best_win_rate,
+            history,
+        }
+    }
+}
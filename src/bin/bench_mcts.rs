@@ -0,0 +1,107 @@
+use betago::ai::mcts::{MctsAI, MctsConfig, Parallelism};
+use betago::ai::tuning::{MatchSettings, win_rate};
+use betago::core::seed;
+use betago::{Board, Stone};
+use std::time::Instant;
+
+const USAGE: &str = "usage: bench_mcts [--compare transpositions|rave|threads] [--playouts N] \
+[--games N] [--size N] [--rave-equivalence K] [--threads N] [--root-parallel] [--seed N]";
+
+/// Compares a search feature against the search without it at equal
+/// playouts: stored nodes and memory for one search from the empty board,
+/// then a match between the two. `threads` compares `--threads` (default:
+/// every core) against a single thread.
+fn main() -> anyhow::Result<()> {
+    let mut config = MctsConfig {
+        playouts: 10000,
+        ..MctsConfig::default()
+    };
+    let mut settings = MatchSettings::default();
+    let mut compare = String::from("transpositions");
+    let mut games = 20;
+    let mut seed: u64 = rand::random();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{flag} needs a value\n{USAGE}"))
+        };
+        match flag.as_str() {
+            "--compare" => compare = value()?,
+            "--playouts" => config.playouts = value()?.parse()?,
+            "--games" => games = value()?.parse()?,
+            "--size" => settings.board_size = value()?.parse()?,
+            "--rave-equivalence" => config.rave_equivalence = value()?.parse()?,
+            "--threads" => config.threads = value()?.parse()?,
+            "--root-parallel" => config.parallelism = Parallelism::Root,
+            "--seed" => seed = value()?.parse()?,
+            _ => anyhow::bail!("unknown argument {flag}\n{USAGE}"),
+        }
+    }
+    config.komi = settings.komi;
+    println!("seed {seed}");
+
+    let (with, without) = match compare.as_str() {
+        "transpositions" => (
+            MctsConfig {
+                transpositions: true,
+                ..config
+            },
+            MctsConfig {
+                transpositions: false,
+                ..config
+            },
+        ),
+        "rave" => (
+            MctsConfig {
+                rave: true,
+                ..config
+            },
+            MctsConfig {
+                rave: false,
+                ..config
+            },
+        ),
+        "threads" => (
+            MctsConfig {
+                threads: match config.threads {
+                    1 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+                    n => n,
+                },
+                ..config
+            },
+            MctsConfig {
+                threads: 1,
+                ..config
+            },
+        ),
+        other => anyhow::bail!("unknown comparison {other}\n{USAGE}"),
+    };
+
+    let board = Board::new(settings.board_size);
+    for (name, config) in [("with", with), ("without", without)] {
+        let start = Instant::now();
+        let stats = MctsAI::new(config, seed).search(&board, Stone::Black).stats;
+        println!(
+            "{name:>7} {compare}: {} nodes, {} shared or duplicate, {:.1} MiB, {:.2?}, {:.0} playouts/s",
+            stats.nodes,
+            stats.transpositions,
+            stats.memory as f64 / (1 << 20) as f64,
+            start.elapsed(),
+            stats.playouts as f64 / start.elapsed().as_secs_f64()
+        );
+    }
+
+    if games > 0 {
+        let rate = win_rate(
+            &mut MctsAI::new(with, seed::derive(seed, 1)),
+            &mut MctsAI::new(without, seed::derive(seed, 2)),
+            games,
+            &settings,
+            &mut seed::rng(seed),
+        );
+        println!("with {compare} wins {rate:.3} of {games} games");
+    }
+    Ok(())
+}
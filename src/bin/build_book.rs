@@ -0,0 +1,83 @@
+use betago::ai::GoAI;
+use betago::ai::book::OpeningBook;
+use betago::ai::heuristic::HeuristicAI;
+use betago::ai::mcts::{MctsAI, MctsConfig};
+use betago::ai::tuning::MatchSettings;
+use betago::core::seed;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: build_book [DIR...] [--self-play N] [--engine heuristic|mcts] \
+[--playouts N] [--size N] [--depth N] [--output BOOK] [--seed N]";
+
+#[derive(Clone, Copy)]
+enum Engine {
+    Heuristic,
+    Mcts,
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut dirs = Vec::new();
+    let mut self_play = 0;
+    let mut engine = Engine::Heuristic;
+    let mut mcts = MctsConfig::default();
+    let mut settings = MatchSettings::default();
+    let mut depth = 16;
+    let mut output = PathBuf::from("opening.book");
+    let mut seed: u64 = rand::random();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{arg} needs a value\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--self-play" => self_play = value()?.parse()?,
+            "--engine" => {
+                engine = match value()?.as_str() {
+                    "heuristic" => Engine::Heuristic,
+                    "mcts" => Engine::Mcts,
+                    other => anyhow::bail!("unknown engine {other}\n{USAGE}"),
+                }
+            }
+            "--playouts" => mcts.playouts = value()?.parse()?,
+            "--size" => settings.board_size = value()?.parse()?,
+            "--depth" => depth = value()?.parse()?,
+            "--output" => output = value()?.into(),
+            "--seed" => seed = value()?.parse()?,
+            flag if flag.starts_with("--") => anyhow::bail!("unknown argument {flag}\n{USAGE}"),
+            dir => dirs.push(PathBuf::from(dir)),
+        }
+    }
+    if dirs.is_empty() && self_play == 0 {
+        anyhow::bail!("nothing to build from\n{USAGE}");
+    }
+
+    let mut book = OpeningBook::new(depth);
+    for dir in &dirs {
+        let (added, skipped) = book.add_directory(dir)?;
+        println!("{}: {added} games, {skipped} skipped", dir.display());
+    }
+    if self_play > 0 {
+        println!("seed {seed}");
+        mcts.komi = settings.komi;
+        let player = |stream: u64| -> Box<dyn GoAI> {
+            match engine {
+                Engine::Heuristic => Box::new(HeuristicAI::default()),
+                Engine::Mcts => Box::new(MctsAI::new(mcts, seed::derive(seed, stream))),
+            }
+        };
+        book.add_self_play(
+            player(1).as_mut(),
+            player(2).as_mut(),
+            self_play,
+            &settings,
+            &mut seed::rng(seed),
+        )?;
+        println!("{self_play} self-play games");
+    }
+
+    book.save(&output)?;
+    println!("{} positions written to {}", book.len(), output.display());
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use betago::ai::train::TrainingExporter;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: export_training DIR... [--size N] [--output DIR] \
+[--shard-size N] [--no-augment] [--seed N]";
+
+/// Converts SGF collections into sharded samples for supervised training
+/// of the policy and value network.
+fn main() -> anyhow::Result<()> {
+    let mut dirs = Vec::new();
+    let mut size = 19;
+    let mut output = PathBuf::from("training");
+    let mut shard_size = None;
+    let mut augment = true;
+    let mut seed: u64 = rand::random();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{arg} needs a value\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--size" => size = value()?.parse()?,
+            "--output" => output = value()?.into(),
+            "--shard-size" => shard_size = Some(value()?.parse()?),
+            "--no-augment" => augment = false,
+            "--seed" => seed = value()?.parse()?,
+            flag if flag.starts_with("--") => anyhow::bail!("unknown argument {flag}\n{USAGE}"),
+            dir => dirs.push(PathBuf::from(dir)),
+        }
+    }
+    if dirs.is_empty() {
+        anyhow::bail!("no game directory given\n{USAGE}");
+    }
+
+    let mut exporter = TrainingExporter::new(size, &output, seed);
+    if let Some(shard_size) = shard_size {
+        exporter.shard_size = shard_size;
+    }
+    exporter.augment = augment;
+    for dir in &dirs {
+        let (added, skipped) = exporter.add_directory(dir)?;
+        println!("{}: {added} games, {skipped} skipped", dir.display());
+    }
+
+    let samples = exporter.samples();
+    let shards = exporter.finish()?;
+    println!(
+        "{samples} samples in {} shards written to {}",
+        shards.len(),
+        output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use betago::ai::mcts::MctsConfig;
+use betago::ai::network::Network;
+use betago::gtp::{Engine, Gtp};
+use std::io::{BufRead, Write};
+
+const USAGE: &str = "usage: gtp [--engine mcts|network] [--weights FILE] [--playouts N] \
+[--threads N] [--komi K] [--ponder] [--no-reuse] [--seed N]";
+
+/// Speaks GTP on stdin and stdout, for use with GUIs and match runners.
+fn main() -> anyhow::Result<()> {
+    let mut config = MctsConfig::default();
+    let mut engine = Engine::Mcts;
+    let mut weights = None;
+    let mut seed: u64 = rand::random();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{flag} needs a value\n{USAGE}"))
+        };
+        match flag.as_str() {
+            "--engine" => {
+                engine = match value()?.as_str() {
+                    "mcts" => Engine::Mcts,
+                    "network" => Engine::Network,
+                    other => anyhow::bail!("unknown engine {other}\n{USAGE}"),
+                }
+            }
+            "--weights" => weights = Some(value()?),
+            "--playouts" => config.playouts = value()?.parse()?,
+            "--threads" => config.threads = value()?.parse()?,
+            "--komi" => config.komi = value()?.parse()?,
+            "--ponder" => config.ponder = true,
+            "--no-reuse" => config.reuse_tree = false,
+            "--seed" => seed = value()?.parse()?,
+            _ => anyhow::bail!("unknown argument {flag}\n{USAGE}"),
+        }
+    }
+
+    let mut gtp = match weights {
+        Some(path) => {
+            let network = Network::load(&path)
+                .map_err(|e| anyhow::anyhow!("cannot load weights from {path}: {e}"))?;
+            Gtp::with_weights(network, config, seed)
+        }
+        None => Gtp::new(engine, config, seed),
+    };
+    let mut stdout = std::io::stdout();
+    for line in std::io::stdin().lock().lines() {
+        if let Some(response) = gtp.handle(&line?) {
+            stdout.write_all(response.as_bytes())?;
+            stdout.flush()?;
+        }
+        if gtp.is_done() {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use betago::ai::patterns::PatternLearner;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: learn_patterns DIR... [--iterations N] [--output PATTERNS]";
+
+fn main() -> anyhow::Result<()> {
+    let mut dirs = Vec::new();
+    let mut iterations = 20;
+    let mut output = PathBuf::from("patterns.bin");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{arg} needs a value\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--iterations" => iterations = value()?.parse()?,
+            "--output" => output = value()?.into(),
+            flag if flag.starts_with("--") => anyhow::bail!("unknown argument {flag}\n{USAGE}"),
+            dir => dirs.push(PathBuf::from(dir)),
+        }
+    }
+    if dirs.is_empty() {
+        anyhow::bail!("no game directory given\n{USAGE}");
+    }
+
+    let mut learner = PatternLearner::new();
+    for dir in &dirs {
+        let (added, skipped) = learner.add_directory(dir)?;
+        println!("{}: {added} games, {skipped} skipped", dir.display());
+    }
+    println!(
+        "{} positions, {} patterns",
+        learner.positions(),
+        learner.patterns()
+    );
+
+    let priors = learner.train(iterations);
+    priors.save(&output)?;
+    println!("written to {}", output.display());
+    Ok(())
+}
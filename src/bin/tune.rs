@@ -0,0 +1,51 @@
+use betago::ai::heuristic::HeuristicAI;
+use betago::ai::tuning::Spsa;
+use betago::core::seed;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: tune [--iterations N] [--games N] [--eval-games N] [--size N] \
+[--start WEIGHTS] [--output WEIGHTS] [--seed N]";
+
+fn main() -> anyhow::Result<()> {
+    let mut spsa = Spsa::default();
+    let mut start = HeuristicAI::default();
+    let mut output = PathBuf::from("heuristic.conf");
+    let mut seed: u64 = rand::random();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{flag} needs a value\n{USAGE}"))
+        };
+        match flag.as_str() {
+            "--iterations" => spsa.iterations = value()?.parse()?,
+            "--games" => spsa.games_per_side = value()?.parse()?,
+            "--eval-games" => spsa.evaluation_games = value()?.parse()?,
+            "--size" => spsa.matches.board_size = value()?.parse()?,
+            "--start" => start = HeuristicAI::load(value()?)?,
+            "--output" => output = value()?.into(),
+            "--seed" => seed = value()?.parse()?,
+            _ => anyhow::bail!("unknown argument {flag}\n{USAGE}"),
+        }
+    }
+
+    println!("seed {seed}");
+    let mut baseline = HeuristicAI::default();
+    let result = spsa.tune(
+        start,
+        &mut baseline,
+        &mut seed::rng(seed),
+        |k, weights, rate| {
+            println!("iteration {k:>3}: win rate {rate:.3} with {weights:?}");
+        },
+    );
+
+    result.best.save(&output)?;
+    println!(
+        "best win rate {:.3} against the baseline, written to {}",
+        result.best_win_rate,
+        output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Expected `key = value` on line {line}")]
+    Syntax { line: usize },
+
+    #[error("Invalid value {value:?} for {key}")]
+    InvalidValue { key: String, value: String },
+
+    #[error("Failed to access config file: {0}")]
+    Io(#[from] std::io::Error),
+}
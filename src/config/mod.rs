@@ -0,0 +1,93 @@
+pub mod error;
+
+pub use error::ConfigError;
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A flat `key = value` file. Keys keep their insertion order so that saving
+/// a loaded file leaves it recognisable; `#` starts a comment line.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigFile {
+    entries: Vec<(String, String)>,
+}
+
+impl ConfigFile {
+    pub fn parse(input: &str) -> Result<Self, ConfigError> {
+        let mut config = ConfigFile::default();
+        for (number, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ConfigError::Syntax { line: number + 1 })?;
+            config.set(key.trim(), value.trim());
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    pub fn raw(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Parses `key` when present; a present but malformed value is an error
+    /// rather than a silent fallback.
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, ConfigError> {
+        self.raw(key)
+            .map(|value| {
+                value.parse().map_err(|_| ConfigError::InvalidValue {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+            })
+            .transpose()
+    }
+
+    pub fn set(&mut self, key: &str, value: impl Display) {
+        let value = value.to_string();
+        match self.entries.iter_mut().find(|(name, _)| name == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+}
+
+impl Display for ConfigFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in &self.entries {
+            writeln!(f, "{key} = {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// `$XDG_CONFIG_HOME/betago/<name>`, falling back to `~/.config` and finally
+/// the working directory.
+pub fn config_path(name: &str) -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    match base {
+        Some(base) => base.join("betago").join(name),
+        None => PathBuf::from(name),
+    }
+}
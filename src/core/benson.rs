@@ -0,0 +1,154 @@
+use crate::core::{Board, Position, Stone};
+
+#[derive(Debug, Clone, Default)]
+pub struct PassAlive {
+    pub chains: Vec<Vec<Position>>,
+    pub territory: Vec<Position>,
+}
+
+impl PassAlive {
+    pub fn is_alive(&self, pos: Position) -> bool {
+        self.chains.iter().any(|chain| chain.contains(&pos))
+    }
+
+    pub fn is_territory(&self, pos: Position) -> bool {
+        self.territory.contains(&pos)
+    }
+}
+
+impl Board {
+    /// Benson's algorithm: finds the chains of `color` that cannot be captured
+    /// even if `color` passes every move, and the regions they own outright.
+    pub fn pass_alive(&self, color: Stone) -> PassAlive {
+        let size = self.size();
+        let stone_at = |pos: Position| self.get_stone(pos).ok().flatten();
+
+        let mut chain_of = vec![usize::MAX; size * size];
+        let mut chains: Vec<Vec<Position>> = Vec::new();
+        let mut region_of = vec![usize::MAX; size * size];
+        let mut regions: Vec<Vec<Position>> = Vec::new();
+
+        for y in 0..size {
+            for x in 0..size {
+                let pos = Position { x, y };
+                let idx = self.pos_to_index(pos);
+
+                if stone_at(pos) == Some(color) {
+                    if chain_of[idx] == usize::MAX {
+                        let chain = self.get_group(pos);
+                        for p in &chain {
+                            chain_of[self.pos_to_index(*p)] = chains.len();
+                        }
+                        chains.push(chain);
+                    }
+                } else if region_of[idx] == usize::MAX {
+                    let mut region = Vec::new();
+                    let mut stack = vec![pos];
+                    region_of[idx] = regions.len();
+                    while let Some(p) = stack.pop() {
+                        region.push(p);
+                        for neighbor in self.get_neighbors(p) {
+                            let n_idx = self.pos_to_index(neighbor);
+                            if stone_at(neighbor) != Some(color) && region_of[n_idx] == usize::MAX {
+                                region_of[n_idx] = regions.len();
+                                stack.push(neighbor);
+                            }
+                        }
+                    }
+                    regions.push(region);
+                }
+            }
+        }
+
+        let adjacent_chains = |pos: Position| -> Vec<usize> {
+            self.get_neighbors(pos)
+                .into_iter()
+                .map(|n| chain_of[self.pos_to_index(n)])
+                .filter(|&c| c != usize::MAX)
+                .collect()
+        };
+
+        let mut bordering: Vec<Vec<usize>> = Vec::with_capacity(regions.len());
+        let mut vital: Vec<Vec<usize>> = Vec::with_capacity(regions.len());
+        for region in &regions {
+            let mut chains_around: Vec<usize> =
+                region.iter().flat_map(|&p| adjacent_chains(p)).collect();
+            chains_around.sort_unstable();
+            chains_around.dedup();
+
+            let vital_to = chains_around
+                .iter()
+                .copied()
+                .filter(|&c| {
+                    region
+                        .iter()
+                        .filter(|&&p| stone_at(p).is_none())
+                        .all(|&p| adjacent_chains(p).contains(&c))
+                })
+                .collect();
+
+            bordering.push(chains_around);
+            vital.push(vital_to);
+        }
+
+        let mut alive_chain = vec![true; chains.len()];
+        let mut live_region = vec![true; regions.len()];
+        loop {
+            let mut changed = false;
+
+            for (c, alive) in alive_chain.iter_mut().enumerate() {
+                if !*alive {
+                    continue;
+                }
+                let healthy = (0..regions.len())
+                    .filter(|&r| live_region[r] && vital[r].contains(&c))
+                    .count();
+                if healthy < 2 {
+                    *alive = false;
+                    changed = true;
+                }
+            }
+
+            for (r, live) in live_region.iter_mut().enumerate() {
+                if *live && bordering[r].iter().any(|&c| !alive_chain[c]) {
+                    *live = false;
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let territory = regions
+            .iter()
+            .enumerate()
+            .filter(|&(r, region)| {
+                live_region[r]
+                    && !bordering[r].is_empty()
+                    && region
+                        .iter()
+                        .filter(|&&p| stone_at(p).is_none())
+                        .all(|&p| !adjacent_chains(p).is_empty())
+            })
+            .flat_map(|(_, region)| region.iter().copied())
+            .collect();
+
+        PassAlive {
+            chains: chains
+                .into_iter()
+                .zip(alive_chain)
+                .filter_map(|(chain, alive)| alive.then_some(chain))
+                .collect(),
+            territory,
+        }
+    }
+
+    pub fn is_pass_alive(&self, pos: Position) -> bool {
+        match self.get_stone(pos) {
+            Ok(Some(color)) => self.pass_alive(color).is_alive(pos),
+            _ => false,
+        }
+    }
+}
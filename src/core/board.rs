@@ -1,6 +1,7 @@
 use crate::GoError;
-use rand::{self, Rng};
-use std::{collections::HashSet, hash::Hash};
+use crate::core::seed;
+use crate::core::symmetry::Symmetry;
+use std::hash::Hash;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Stone {
@@ -23,14 +24,32 @@ pub struct Position {
     pub y: usize,
 }
 
+impl Position {
+    pub fn transform(self, symmetry: Symmetry, size: usize) -> Position {
+        symmetry.apply(self, size)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveOutcome {
+    pub captured: Vec<Position>,
+    pub atari: bool,
+    pub ko_point: Option<Position>,
+    pub hash: u64,
+}
+
+/// Moves a board remembers, for the network's history planes.
+pub const MOVE_HISTORY: usize = 8;
+
 #[derive(Debug, Clone)]
 pub struct Board {
     size: usize,
     grid: Vec<Option<Stone>>,
 
-    zobrist_table: Vec<Vec<u64>>,
     current_hash: u64,
     previous_hash: Option<u64>,
+    /// Latest move first; `None` for passes and before the first move.
+    recent_moves: [Option<Position>; MOVE_HISTORY],
 }
 
 impl Board {
@@ -39,20 +58,12 @@ impl Board {
             panic!("Size of the board should be positive");
         }
 
-        let mut zobrist_table = vec![vec![0; 2]; size * size];
-        let mut rng = rand::rng();
-
-        (0..size * size).for_each(|i| {
-            zobrist_table[i][0] = rng.random();
-            zobrist_table[i][1] = rng.random();
-        });
-
         Board {
             size,
             grid: vec![None; size * size],
             previous_hash: None,
             current_hash: 0,
-            zobrist_table,
+            recent_moves: [None; MOVE_HISTORY],
         }
     }
 
@@ -64,18 +75,10 @@ impl Board {
     fn update_hash(&mut self, pos: Position, stone: Option<Stone>) {
         let idx = self.pos_to_index(pos);
         if let Some(s) = self.grid[idx] {
-            let color_idx = match s {
-                Stone::Black => 0,
-                Stone::White => 1,
-            };
-            self.current_hash ^= self.zobrist_table[idx][color_idx];
+            self.current_hash ^= zobrist_key(idx, s);
         }
         if let Some(s) = stone {
-            let color_idx = match s {
-                Stone::Black => 0,
-                Stone::White => 1,
-            };
-            self.current_hash ^= self.zobrist_table[idx][color_idx];
+            self.current_hash ^= zobrist_key(idx, s);
         }
     }
 
@@ -97,20 +100,24 @@ impl Board {
     }
 
     pub fn place_stone(&mut self, pos: Position, stone: Stone) -> Result<usize, GoError> {
+        self.play(pos, stone).map(|outcome| outcome.captured.len())
+    }
+
+    pub fn play(&mut self, pos: Position, stone: Stone) -> Result<MoveOutcome, GoError> {
         self.internal_move_validate(pos)?;
 
-        let current_hash = self.calculate_hash();
+        let current_hash = self.current_hash;
 
         let index = self.pos_to_index(pos);
-        self.grid[index] = Some(stone);
         self.update_hash(pos, Some(stone));
+        self.grid[index] = Some(stone);
 
         let opponent = stone.opposite();
 
-        let mut captured = HashSet::new();
+        let mut captured = Vec::new();
 
         for neighbor in self.get_neighbors(pos) {
-            if self.get_stone(neighbor)? == Some(opponent) {
+            if self.get_stone(neighbor)? == Some(opponent) && !captured.contains(&neighbor) {
                 let group = self.get_group(neighbor);
                 if !self.has_liberties(&group) {
                     captured.extend(group);
@@ -120,8 +127,8 @@ impl Board {
 
         let current_group = self.get_group(pos);
         if !self.has_liberties(&current_group) && captured.is_empty() {
-            self.grid[index] = None;
             self.update_hash(pos, None);
+            self.grid[index] = None;
             return Err(GoError::suicidal_move());
         }
 
@@ -132,39 +139,135 @@ impl Board {
             self.grid[index] = None;
         }
 
-        if captured_len == 1 {
-            if let Some(prev_hash) = self.previous_hash {
-                if prev_hash == self.calculate_hash() {
-                    self.grid[index] = None;
-                    for pos in self.get_group(pos) {
-                        self.update_hash(pos, None);
-                    }
-                    return Err(GoError::ko_rule_violation());
-                }
+        if captured_len == 1
+            && let Some(prev_hash) = self.previous_hash
+            && prev_hash == self.current_hash
+        {
+            self.update_hash(pos, None);
+            self.grid[index] = None;
+            for pos in &captured {
+                self.update_hash(*pos, Some(opponent));
+                let index = self.pos_to_index(*pos);
+                self.grid[index] = Some(opponent);
             }
+            return Err(GoError::ko_rule_violation());
         }
 
         if captured_len > 0 {
             self.previous_hash = Some(current_hash);
         }
 
-        Ok(captured_len)
+        let atari = self.get_neighbors(pos).into_iter().any(|neighbor| {
+            self.grid[self.pos_to_index(neighbor)] == Some(opponent)
+                && self.count_liberties(&self.get_group(neighbor)) == 1
+        });
+
+        self.recent_moves.rotate_right(1);
+        self.recent_moves[0] = Some(pos);
+
+        let ko_point = if captured_len == 1
+            && current_group.len() == 1
+            && self.count_liberties(&current_group) == 1
+        {
+            Some(captured[0])
+        } else {
+            None
+        };
+
+        Ok(MoveOutcome {
+            captured,
+            atari,
+            ko_point,
+            hash: self.current_hash,
+        })
     }
 
     fn calculate_hash(&self) -> u64 {
         let mut hash = 0;
         for (i, stone) in self.grid.iter().enumerate() {
             if let Some(s) = stone {
-                let color_idx = match s {
-                    Stone::Black => 0,
-                    Stone::White => 1,
-                };
-                hash ^= self.zobrist_table[i][color_idx];
+                hash ^= zobrist_key(i, *s);
             }
         }
         hash
     }
 
+    pub fn hash(&self) -> u64 {
+        self.current_hash
+    }
+
+    pub fn previous_hash(&self) -> Option<u64> {
+        self.previous_hash
+    }
+
+    /// Ko state to fold into a position key. `play` rejects a retake by
+    /// comparing against `previous_hash`, so that hash is all a key needs,
+    /// and reading it costs nothing.
+    pub fn ko_key(&self) -> u64 {
+        self.previous_hash.map_or(0, |hash| hash.rotate_left(17))
+    }
+
+    /// Records a pass in the move history.
+    pub fn pass(&mut self) {
+        self.recent_moves.rotate_right(1);
+        self.recent_moves[0] = None;
+    }
+
+    /// The last `MOVE_HISTORY` moves, latest first; `None` is a pass or no
+    /// move at all.
+    pub fn recent_moves(&self) -> &[Option<Position>; MOVE_HISTORY] {
+        &self.recent_moves
+    }
+
+    /// The board with every stone and recent move moved by `symmetry`. The
+    /// ko history cannot be carried over, so the result has no previous
+    /// position.
+    pub fn transform(&self, symmetry: Symmetry) -> Board {
+        let mut board = Board::new(self.size);
+        for (i, &stone) in self.grid.iter().enumerate() {
+            let pos = Position {
+                x: i % self.size,
+                y: i / self.size,
+            };
+            let index = board.pos_to_index(pos.transform(symmetry, self.size));
+            board.grid[index] = stone;
+        }
+        board.current_hash = board.calculate_hash();
+        board.recent_moves = self
+            .recent_moves
+            .map(|mv| mv.map(|pos| pos.transform(symmetry, self.size)));
+        board
+    }
+
+    /// Hash of the board transformed by each symmetry, in `Symmetry::ALL`
+    /// order, without building the transformed boards.
+    pub fn symmetric_hashes(&self) -> [u64; 8] {
+        let mut hashes = [0; 8];
+        for (i, stone) in self.grid.iter().enumerate() {
+            let Some(stone) = *stone else {
+                continue;
+            };
+            let pos = Position {
+                x: i % self.size,
+                y: i / self.size,
+            };
+            for (hash, symmetry) in hashes.iter_mut().zip(Symmetry::ALL) {
+                *hash ^= zobrist_key(self.pos_to_index(pos.transform(symmetry, self.size)), stone);
+            }
+        }
+        hashes
+    }
+
+    /// The smallest hash among the symmetric variants of the board and the
+    /// first symmetry that produces it.
+    pub fn canonical_hash(&self) -> (u64, Symmetry) {
+        self.symmetric_hashes()
+            .into_iter()
+            .zip(Symmetry::ALL)
+            .min()
+            .expect("there are eight symmetries")
+    }
+
     pub fn get_stone(&self, pos: Position) -> Result<Option<Stone>, GoError> {
         if !self.is_on_board(pos) {
             return Err(GoError::OutOfBounds { pos });
@@ -205,10 +308,10 @@ impl Board {
         visited[self.pos_to_index(pos)] = true;
         group.push(pos);
         for neighbor in self.get_neighbors(pos) {
-            if let Some(s) = self.get_stone(neighbor).unwrap_or(None) {
-                if s == stone {
-                    self.find_connected_stones(neighbor, stone, visited, group);
-                }
+            if let Some(s) = self.get_stone(neighbor).unwrap_or(None)
+                && s == stone
+            {
+                self.find_connected_stones(neighbor, stone, visited, group);
             }
         }
     }
@@ -234,14 +337,18 @@ impl Board {
     }
 
     pub fn count_liberties(&self, group: &[Position]) -> usize {
-        let mut liberties = 0;
+        self.liberties(group).len()
+    }
+
+    pub fn liberties(&self, group: &[Position]) -> Vec<Position> {
+        let mut liberties = Vec::new();
         let mut counted = vec![false; self.size * self.size];
 
         for pos in group {
             for neighbor in self.get_neighbors(*pos) {
                 let neighbor_index = self.pos_to_index(neighbor);
-                if !counted[neighbor_index] && self.get_stone(neighbor).unwrap_or(None).is_none() {
-                    liberties += 1;
+                if !counted[neighbor_index] && self.grid[neighbor_index].is_none() {
+                    liberties.push(neighbor);
                     counted[neighbor_index] = true;
                 }
             }
@@ -249,11 +356,49 @@ impl Board {
 
         liberties
     }
+
+    /// The point `player` may not play because it would retake a ko.
+    pub fn ko_point(&self, player: Stone) -> Option<Position> {
+        let opponent = player.opposite();
+        (0..self.size * self.size)
+            .map(|i| Position {
+                x: i % self.size,
+                y: i / self.size,
+            })
+            .find(|&pos| {
+                let neighbors = self.get_neighbors(pos);
+                self.grid[self.pos_to_index(pos)].is_none()
+                    && neighbors
+                        .iter()
+                        .all(|&n| self.grid[self.pos_to_index(n)] == Some(opponent))
+                    && neighbors
+                        .iter()
+                        .filter(|&&n| {
+                            let group = self.get_group(n);
+                            group.len() == 1 && self.count_liberties(&group) == 1
+                        })
+                        .count()
+                        == 1
+                    && !self.is_valid_move(pos, player)
+            })
+    }
+
     fn has_liberties(&self, group: &[Position]) -> bool {
         self.count_liberties(group) > 0
     }
 }
 
+/// Zobrist key of a stone on a point. Keys are derived from a fixed seed, so
+/// hashes are reproducible between runs and agree across boards.
+fn zobrist_key(idx: usize, stone: Stone) -> u64 {
+    const ZOBRIST_SEED: u64 = 0x6265_7461_676F_2D7A;
+    let color = match stone {
+        Stone::Black => 0,
+        Stone::White => 1,
+    };
+    seed::mix(ZOBRIST_SEED ^ (idx as u64 * 2 + color))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,6 +497,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hash_follows_captures() {
+        let mut board = Board::new(9);
+        let mut expected = board.clone();
+
+        board
+            .place_stone(Position { x: 0, y: 0 }, Stone::Black)
+            .unwrap();
+        board
+            .place_stone(Position { x: 1, y: 0 }, Stone::White)
+            .unwrap();
+        board
+            .place_stone(Position { x: 0, y: 1 }, Stone::White)
+            .unwrap();
+
+        expected
+            .place_stone(Position { x: 1, y: 0 }, Stone::White)
+            .unwrap();
+        expected
+            .place_stone(Position { x: 0, y: 1 }, Stone::White)
+            .unwrap();
+
+        assert_ne!(board.hash(), 0, "Hash should include placed stones");
+        assert_eq!(
+            board.hash(),
+            expected.hash(),
+            "Captured stones should be removed from the hash"
+        );
+    }
+
+    #[test]
+    fn play_reports_captured_stones() {
+        let mut board = Board::new(9);
+        board
+            .place_stone(Position { x: 0, y: 0 }, Stone::Black)
+            .unwrap();
+        board
+            .place_stone(Position { x: 1, y: 0 }, Stone::Black)
+            .unwrap();
+        board
+            .place_stone(Position { x: 2, y: 0 }, Stone::White)
+            .unwrap();
+        board
+            .place_stone(Position { x: 0, y: 1 }, Stone::White)
+            .unwrap();
+
+        let outcome = board.play(Position { x: 1, y: 1 }, Stone::White).unwrap();
+        assert_eq!(
+            outcome.captured,
+            vec![Position { x: 1, y: 0 }, Position { x: 0, y: 0 }],
+            "Both black stones should be reported"
+        );
+        assert!(!outcome.atari);
+        assert_eq!(outcome.ko_point, None);
+        assert_eq!(outcome.hash, board.hash());
+    }
+
+    #[test]
+    fn play_reports_atari_and_ko_point() {
+        let mut board = Board::new(5);
+        let black = [(0, 1), (1, 0), (2, 1), (1, 2)];
+        for (x, y) in black {
+            board.place_stone(Position { x, y }, Stone::Black).unwrap();
+        }
+        board
+            .place_stone(Position { x: 0, y: 2 }, Stone::White)
+            .unwrap();
+        board
+            .place_stone(Position { x: 2, y: 2 }, Stone::White)
+            .unwrap();
+
+        let atari = board.play(Position { x: 1, y: 3 }, Stone::White).unwrap();
+        assert!(atari.atari, "Black stone should be left with one liberty");
+        assert!(atari.captured.is_empty());
+        assert_eq!(atari.ko_point, None);
+
+        let ko = board.play(Position { x: 1, y: 1 }, Stone::White).unwrap();
+        assert_eq!(ko.captured, vec![Position { x: 1, y: 2 }]);
+        assert_eq!(
+            ko.ko_point,
+            Some(Position { x: 1, y: 2 }),
+            "Single stone capture should create a ko"
+        );
+        assert_eq!(board.ko_point(Stone::Black), Some(Position { x: 1, y: 2 }));
+        assert_eq!(board.ko_point(Stone::White), None);
+    }
+
     #[test]
     #[should_panic]
     fn create_zero_size_board() {
@@ -1,22 +1,91 @@
+use crate::core::MoveOutcome;
+use crate::core::scoring::{self, Rules, Score};
 use crate::{Board, GoError, Position, Stone};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Play(Stone, Position),
+    Pass(Stone),
+}
+
+impl Move {
+    pub fn stone(&self) -> Stone {
+        match self {
+            Move::Play(stone, _) | Move::Pass(stone) => *stone,
+        }
+    }
+
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Move::Play(_, pos) => Some(*pos),
+            Move::Pass(_) => None,
+        }
+    }
+}
+
 pub struct Game {
     board: Board,
     current_player: Stone,
     captured_stones: (usize, usize),
     current_move: usize,
     passes_count: u8,
+    rules: Rules,
+    komi: f32,
+    moves: Vec<Move>,
+    move_numbers: Vec<Option<usize>>,
+    handicap: Vec<Position>,
 }
 
 impl Game {
     pub fn new(board_size: usize) -> Self {
+        Self::with_rules(board_size, Rules::default(), 6.5)
+    }
+
+    pub fn with_rules(board_size: usize, rules: Rules, komi: f32) -> Self {
         Game {
             board: Board::new(board_size),
             current_player: Stone::Black,
             captured_stones: (0, 0),
             current_move: 1,
             passes_count: 0,
+            rules,
+            komi,
+            moves: Vec::new(),
+            move_numbers: vec![None; board_size * board_size],
+            handicap: Vec::new(),
+        }
+    }
+
+    /// Starts a game with `stones` black handicap stones on the standard
+    /// points; White moves first.
+    pub fn with_handicap(board_size: usize, rules: Rules, komi: f32, stones: usize) -> Self {
+        Self::with_handicap_stones(board_size, rules, komi, handicap_points(board_size, stones))
+            .expect("standard handicap points are distinct points on the board")
+    }
+
+    /// Starts a game with black handicap stones on `stones`, as given by a
+    /// game record; White moves first unless there are none. Fails when a
+    /// stone is off the board or on a point already taken.
+    pub fn with_handicap_stones(
+        board_size: usize,
+        rules: Rules,
+        komi: f32,
+        stones: Vec<Position>,
+    ) -> Result<Self, GoError> {
+        let mut game = Self::with_rules(board_size, rules, komi);
+        game.handicap = stones;
+        game.place_handicap()?;
+        Ok(game)
+    }
+
+    fn place_handicap(&mut self) -> Result<(), GoError> {
+        for &pos in &self.handicap {
+            self.board.place_stone(pos, Stone::Black)?;
         }
+        if !self.handicap.is_empty() {
+            self.current_player = Stone::White;
+        }
+        Ok(())
     }
 
     pub fn reset(&mut self) {
@@ -25,6 +94,10 @@ impl Game {
         self.captured_stones = (0, 0);
         self.current_move = 1;
         self.passes_count = 0;
+        self.moves.clear();
+        self.move_numbers.fill(None);
+        self.place_handicap()
+            .expect("the handicap was placed when the game started");
     }
 
     #[inline]
@@ -33,10 +106,24 @@ impl Game {
     }
 
     pub fn pass(&mut self) {
+        self.board.pass();
+        self.moves.push(Move::Pass(self.current_player));
         self.passes_count += 1;
         self.switch_player();
     }
 
+    /// Hands the move to `stone` without recording a pass, for protocols
+    /// that let either side move at any time.
+    pub fn set_current_player(&mut self, stone: Stone) {
+        self.current_player = stone;
+    }
+
+    /// Lets play continue after two passes, leaving it to the caller to
+    /// decide when the game ends.
+    pub fn resume(&mut self) {
+        self.passes_count = 0;
+    }
+
     pub fn is_game_over(&self) -> Option<Stone> {
         match self.passes_count {
             2 => Some(self.determine_winner()),
@@ -44,19 +131,26 @@ impl Game {
         }
     }
     fn determine_winner(&self) -> Stone {
-        if self.captured_stones.0 > self.captured_stones.1 {
-            Stone::Black
-        } else {
-            Stone::White
-        }
+        self.score(&[]).winner()
+    }
+
+    pub fn score(&self, dead: &[Position]) -> Score {
+        scoring::score(
+            &self.board,
+            dead,
+            self.captured_stones,
+            self.rules,
+            self.komi,
+        )
     }
 
-    pub fn make_move(&mut self, pos: Position) -> Result<(), GoError> {
+    pub fn make_move(&mut self, pos: Position) -> Result<MoveOutcome, GoError> {
         if self.passes_count == 2 {
             return Err(GoError::GameOver);
         }
 
-        let captured_count = self.board.place_stone(pos, self.current_player)?;
+        let outcome = self.board.play(pos, self.current_player)?;
+        let captured_count = outcome.captured.len();
 
         match self.current_player {
             Stone::Black => self.captured_stones.0 += captured_count,
@@ -64,13 +158,16 @@ impl Game {
         }
 
         self.current_move += 1;
-
-        if self.current_player == Stone::White {
-            self.passes_count = 0;
+        self.moves.push(Move::Play(self.current_player, pos));
+        for &captured in &outcome.captured {
+            self.move_numbers[self.board.pos_to_index(captured)] = None;
         }
+        self.move_numbers[self.board.pos_to_index(pos)] = Some(self.moves.len());
+
+        self.passes_count = 0;
         self.switch_player();
 
-        Ok(())
+        Ok(outcome)
     }
 
     pub fn board_size(&self) -> usize {
@@ -93,6 +190,38 @@ impl Game {
         self.is_game_over()
     }
 
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn komi(&self) -> f32 {
+        self.komi
+    }
+
+    pub fn set_komi(&mut self, komi: f32) {
+        self.komi = komi;
+    }
+
+    pub fn handicap(&self) -> &[Position] {
+        &self.handicap
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn last_move(&self) -> Option<Position> {
+        self.moves.last().and_then(Move::position)
+    }
+
+    /// Number of the move that placed the stone currently on `pos`.
+    pub fn move_number(&self, pos: Position) -> Option<usize> {
+        self.move_numbers
+            .get(self.board.pos_to_index(pos))
+            .copied()
+            .flatten()
+    }
+
     pub fn board_state(&self) -> &Board {
         &self.board
     }
@@ -101,3 +230,34 @@ impl Game {
         self.board.get_stone(pos)
     }
 }
+
+/// Standard handicap placement: opposite corners first, then the remaining
+/// corners, the centre for odd counts and the side points. A single stone of
+/// handicap places no stones; komi is left to the caller.
+pub fn handicap_points(size: usize, stones: usize) -> Vec<Position> {
+    if stones < 2 || size < 7 {
+        return Vec::new();
+    }
+
+    let edge = if size >= 13 { 3 } else { 2 };
+    let far = size - 1 - edge;
+    let mid = size / 2;
+    let has_center = size % 2 == 1;
+    let max = if has_center { 9 } else { 4 };
+    let stones = stones.min(max);
+
+    let at = |x, y| Position { x, y };
+    let mut points = vec![at(far, edge), at(edge, far), at(far, far), at(edge, edge)];
+    points.truncate(stones.min(4));
+
+    if stones >= 6 {
+        points.extend([at(edge, mid), at(far, mid)]);
+    }
+    if stones >= 8 {
+        points.extend([at(mid, edge), at(mid, far)]);
+    }
+    if stones >= 5 && stones % 2 == 1 {
+        points.push(at(mid, mid));
+    }
+    points
+}
@@ -1,6 +1,14 @@
+pub mod benson;
 pub mod board;
 pub mod error;
 pub mod game;
+pub mod scoring;
+pub mod seed;
+pub mod seki;
+pub mod symmetry;
 
-pub use board::{Board, Position, Stone};
+pub use benson::PassAlive;
+pub use board::{Board, MoveOutcome, Position, Stone};
 pub use error::GoError;
+pub use scoring::{Rules, Score};
+pub use symmetry::Symmetry;
@@ -0,0 +1,144 @@
+use crate::core::{Board, Position, Stone};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rules {
+    #[default]
+    Japanese,
+    Chinese,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Score {
+    pub black: f32,
+    pub white: f32,
+    pub black_territory: Vec<Position>,
+    pub white_territory: Vec<Position>,
+    pub dead: Vec<Position>,
+    pub seki: Vec<Position>,
+}
+
+impl Score {
+    pub fn winner(&self) -> Stone {
+        if self.black > self.white {
+            Stone::Black
+        } else {
+            Stone::White
+        }
+    }
+
+    pub fn margin(&self) -> f32 {
+        (self.black - self.white).abs()
+    }
+}
+
+/// Scores a finished position. `dead` may name any stone of a dead group;
+/// groups in seki are never removed. Shared liberties of seki groups are never
+/// territory, and under Japanese rules neither are the eyes of seki groups.
+pub fn score(
+    board: &Board,
+    dead: &[Position],
+    captures: (usize, usize),
+    rules: Rules,
+    komi: f32,
+) -> Score {
+    let size = board.size();
+    let seki: Vec<Position> = board.seki_groups().into_iter().flatten().collect();
+    let seki_liberties = board.seki_liberties();
+
+    let mut grid: Vec<Option<Stone>> = (0..size * size)
+        .map(|i| {
+            board
+                .get_stone(Position {
+                    x: i % size,
+                    y: i / size,
+                })
+                .ok()
+                .flatten()
+        })
+        .collect();
+
+    let mut removed = Vec::new();
+    for &pos in dead {
+        if seki.contains(&pos) || removed.contains(&pos) {
+            continue;
+        }
+        for stone in board.get_group(pos) {
+            grid[board.pos_to_index(stone)] = None;
+            removed.push(stone);
+        }
+    }
+    let dead_black = removed
+        .iter()
+        .filter(|&&p| board.get_stone(p).ok().flatten() == Some(Stone::Black))
+        .count();
+    let dead_white = removed.len() - dead_black;
+
+    let mut black_territory = Vec::new();
+    let mut white_territory = Vec::new();
+    let mut visited = vec![false; size * size];
+
+    for start in 0..size * size {
+        if visited[start] || grid[start].is_some() {
+            continue;
+        }
+
+        let mut region = Vec::new();
+        let mut borders = (false, false);
+        let mut touches_seki = false;
+        let mut stack = vec![Position {
+            x: start % size,
+            y: start / size,
+        }];
+        visited[start] = true;
+
+        while let Some(pos) = stack.pop() {
+            region.push(pos);
+            for neighbor in board.get_neighbors(pos) {
+                let idx = board.pos_to_index(neighbor);
+                match grid[idx] {
+                    Some(Stone::Black) => borders.0 = true,
+                    Some(Stone::White) => borders.1 = true,
+                    None if !visited[idx] => {
+                        visited[idx] = true;
+                        stack.push(neighbor);
+                    }
+                    None => {}
+                }
+                if grid[idx].is_some() && seki.contains(&neighbor) {
+                    touches_seki = true;
+                }
+            }
+        }
+
+        let neutral = region.iter().any(|p| seki_liberties.contains(p))
+            || (rules == Rules::Japanese && touches_seki);
+        if neutral {
+            continue;
+        }
+        match borders {
+            (true, false) => black_territory.extend(region),
+            (false, true) => white_territory.extend(region),
+            _ => {}
+        }
+    }
+
+    let (black, white) = match rules {
+        Rules::Japanese => (
+            black_territory.len() + captures.0 + dead_white,
+            white_territory.len() + captures.1 + dead_black,
+        ),
+        Rules::Chinese => (
+            black_territory.len() + grid.iter().filter(|s| **s == Some(Stone::Black)).count(),
+            white_territory.len() + grid.iter().filter(|s| **s == Some(Stone::White)).count(),
+        ),
+    };
+
+    Score {
+        black: black as f32,
+        white: white as f32 + komi,
+        black_territory,
+        white_territory,
+        dead: removed,
+        seki,
+    }
+}
@@ -0,0 +1,22 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// The SplitMix64 output function: a cheap, well-distributed bijection on
+/// `u64`, stable across platforms.
+pub fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Independent sub-seed for one consumer of a game seed, e.g. one per player.
+pub fn derive(seed: u64, stream: u64) -> u64 {
+    mix(seed ^ mix(stream))
+}
+
+/// Generator for a seed. `StdRng` may change algorithm between `rand`
+/// releases, so a seed only replays the same game on the same `rand` version.
+pub fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
@@ -0,0 +1,92 @@
+use crate::core::{Board, Position, Stone};
+
+impl Board {
+    /// Shared liberties of a black and a white group in mutual life. Neither
+    /// side can fill the point without self-atari, and apart from the
+    /// liberties the two groups share, each has only its own eyes: none or
+    /// one, the same on both sides. A group with any other liberty is in a
+    /// capturing race instead.
+    pub fn seki_liberties(&self) -> Vec<Position> {
+        let mut liberties = Vec::new();
+
+        for y in 0..self.size() {
+            for x in 0..self.size() {
+                let pos = Position { x, y };
+                if self.get_stone(pos).ok().flatten().is_some() {
+                    continue;
+                }
+
+                let mut black = Vec::new();
+                let mut white = Vec::new();
+                for neighbor in self.get_neighbors(pos) {
+                    let groups = match self.get_stone(neighbor).ok().flatten() {
+                        Some(Stone::Black) => &mut black,
+                        Some(Stone::White) => &mut white,
+                        None => continue,
+                    };
+                    if !groups.iter().any(|g: &Vec<Position>| g.contains(&neighbor)) {
+                        groups.push(self.get_group(neighbor));
+                    }
+                }
+
+                let mutual = black
+                    .iter()
+                    .any(|b| white.iter().any(|w| self.in_mutual_life(b, w)));
+                if mutual && !self.can_fill(pos, Stone::Black) && !self.can_fill(pos, Stone::White)
+                {
+                    liberties.push(pos);
+                }
+            }
+        }
+
+        liberties
+    }
+
+    pub fn seki_groups(&self) -> Vec<Vec<Position>> {
+        let mut groups: Vec<Vec<Position>> = Vec::new();
+
+        for liberty in self.seki_liberties() {
+            for neighbor in self.get_neighbors(liberty) {
+                if self.get_stone(neighbor).ok().flatten().is_some()
+                    && !groups.iter().any(|g| g.contains(&neighbor))
+                {
+                    groups.push(self.get_group(neighbor));
+                }
+            }
+        }
+
+        groups
+    }
+
+    pub fn is_in_seki(&self, pos: Position) -> bool {
+        self.seki_groups().iter().any(|g| g.contains(&pos))
+    }
+
+    fn in_mutual_life(&self, black: &[Position], white: &[Position]) -> bool {
+        let black_liberties = self.liberties(black);
+        let white_liberties = self.liberties(white);
+        let eyes = |group: &[Position], own: &[Position], other: &[Position]| {
+            let private: Vec<&Position> = own.iter().filter(|p| !other.contains(p)).collect();
+            private
+                .iter()
+                .all(|&&p| self.get_neighbors(p).iter().all(|n| group.contains(n)))
+                .then_some(private.len())
+        };
+
+        match (
+            eyes(black, &black_liberties, &white_liberties),
+            eyes(white, &white_liberties, &black_liberties),
+        ) {
+            (Some(b), Some(w)) => b == w && b <= 1,
+            _ => false,
+        }
+    }
+
+    fn can_fill(&self, pos: Position, stone: Stone) -> bool {
+        let mut board = self.clone();
+        match board.place_stone(pos, stone) {
+            Ok(captured) => captured > 0 || board.count_liberties(&board.get_group(pos)) > 1,
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::core::Position;
+
+/// The eight symmetries of a square board (the dihedral group D4).
+/// Rotations are clockwise on screen, where `y` grows downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Applies the symmetry to an offset relative to a point, e.g. a
+    /// pattern neighbour.
+    pub fn apply_offset(self, (dx, dy): (i32, i32)) -> (i32, i32) {
+        match self {
+            Symmetry::Identity => (dx, dy),
+            Symmetry::Rotate90 => (-dy, dx),
+            Symmetry::Rotate180 => (-dx, -dy),
+            Symmetry::Rotate270 => (dy, -dx),
+            Symmetry::FlipHorizontal => (-dx, dy),
+            Symmetry::FlipVertical => (dx, -dy),
+            Symmetry::Transpose => (dy, dx),
+            Symmetry::AntiTranspose => (-dy, -dx),
+        }
+    }
+
+    /// Applies the symmetry to a point of a `size` board, around its centre.
+    pub fn apply(self, pos: Position, size: usize) -> Position {
+        let last = size as i32 - 1;
+        let (x, y) = self.apply_offset((2 * pos.x as i32 - last, 2 * pos.y as i32 - last));
+        Position {
+            x: ((x + last) / 2) as usize,
+            y: ((y + last) / 2) as usize,
+        }
+    }
+}
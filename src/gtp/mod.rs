@@ -0,0 +1,343 @@
+use crate::ai::GoAI;
+use crate::ai::mcts::{MctsAI, MctsConfig};
+use crate::ai::network::{Architecture, Network, NetworkAI};
+use crate::ai::ownership::{MonteCarloEstimator, OwnershipEstimator, dead_stones};
+use crate::core::game::Game;
+use crate::core::{Board, Position, Rules, Stone, seed};
+use std::sync::Arc;
+
+const COLUMNS: &str = "ABCDEFGHJKLMNOPQRSTUVWXYZ";
+const MAX_SIZE: usize = 25;
+const SCORING_STREAM: u64 = 0;
+
+const COMMANDS: [&str; 16] = [
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "quit",
+    "boardsize",
+    "clear_board",
+    "komi",
+    "play",
+    "genmove",
+    "showboard",
+    "final_score",
+    "betago-ponder",
+    "betago-reuse_tree",
+    "betago-playouts",
+];
+
+/// Which search answers `genmove`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// UCT with playouts.
+    Mcts,
+    /// PUCT guided by a network, with untrained weights unless some are
+    /// loaded.
+    Network,
+}
+
+/// A Go Text Protocol (version 2) front end for the search engines. After
+/// each `genmove` the engine ponders, if enabled, until the next command.
+pub struct Gtp {
+    game: Game,
+    kind: Engine,
+    config: MctsConfig,
+    /// Loaded network weights, which fix the board size.
+    weights: Option<Arc<Network>>,
+    engine: Box<dyn GoAI>,
+    seed: u64,
+    engines: u64,
+    quit: bool,
+}
+
+impl Gtp {
+    pub fn new(kind: Engine, config: MctsConfig, seed: u64) -> Self {
+        Self::build(kind, None, config, seed)
+    }
+
+    /// Plays with the network engine over `network`, on boards of the size
+    /// it was built for.
+    pub fn with_weights(network: Network, config: MctsConfig, seed: u64) -> Self {
+        Self::build(Engine::Network, Some(Arc::new(network)), config, seed)
+    }
+
+    fn build(kind: Engine, weights: Option<Arc<Network>>, config: MctsConfig, seed: u64) -> Self {
+        let size = weights
+            .as_ref()
+            .map_or(19, |network| network.architecture().board_size);
+        Gtp {
+            game: Game::with_rules(size, Rules::Chinese, config.komi),
+            kind,
+            config,
+            engine: build_engine(kind, weights.as_ref(), config, size, seed::derive(seed, 1)),
+            weights,
+            seed,
+            engines: 1,
+            quit: false,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        self.game.board_state()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.quit
+    }
+
+    pub fn is_pondering(&self) -> bool {
+        self.engine.is_pondering()
+    }
+
+    /// Answers one line of input, or returns `None` for blank lines and
+    /// comments.
+    pub fn handle(&mut self, line: &str) -> Option<String> {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let mut words = line.split_whitespace();
+        let first = words.next()?;
+        let (id, command) = match first.parse::<u64>() {
+            Ok(id) => (Some(id), words.next().unwrap_or_default()),
+            Err(_) => (None, first),
+        };
+        let args: Vec<&str> = words.collect();
+
+        let id = id.map(|id| id.to_string()).unwrap_or_default();
+        Some(match self.execute(command, &args) {
+            Ok(reply) => format!("={id} {reply}\n\n"),
+            Err(message) => format!("?{id} {message}\n\n"),
+        })
+    }
+
+    fn execute(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        if command != "genmove" && command != "showboard" {
+            self.engine.stop_pondering();
+        }
+        match command {
+            "protocol_version" => Ok("2".to_string()),
+            "name" => Ok("betago".to_string()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            "known_command" => Ok(COMMANDS.contains(&arg(args, 0)?).to_string()),
+            "list_commands" => Ok(COMMANDS.join("\n")),
+            "quit" => {
+                self.quit = true;
+                Ok(String::new())
+            }
+            "boardsize" => {
+                let size: usize = arg(args, 0)?.parse().map_err(|_| "syntax error")?;
+                let fixed = self
+                    .weights
+                    .as_ref()
+                    .map(|network| network.architecture().board_size);
+                if !(1..=MAX_SIZE).contains(&size) || fixed.is_some_and(|fixed| fixed != size) {
+                    return Err("unacceptable size".to_string());
+                }
+                self.game = Game::with_rules(size, Rules::Chinese, self.config.komi);
+                self.new_engine();
+                Ok(String::new())
+            }
+            "clear_board" => {
+                self.game =
+                    Game::with_rules(self.game.board_size(), Rules::Chinese, self.config.komi);
+                self.new_engine();
+                Ok(String::new())
+            }
+            "komi" => {
+                self.config.komi = arg(args, 0)?.parse().map_err(|_| "syntax error")?;
+                self.game.set_komi(self.config.komi);
+                // Values searched under the old komi would mislead the new search.
+                self.new_engine();
+                Ok(String::new())
+            }
+            "play" => {
+                let color = parse_color(arg(args, 0)?)?;
+                let vertex = parse_vertex(arg(args, 1)?, self.game.board_size())?;
+                if let Some(pos) = vertex
+                    && !self.is_legal(pos, color)
+                {
+                    return Err("illegal move".to_string());
+                }
+                self.give_turn(color);
+                match vertex {
+                    Some(pos) => self
+                        .game
+                        .make_move(pos)
+                        .map(|_| String::new())
+                        .map_err(|_| "illegal move".to_string()),
+                    None => {
+                        self.game.pass();
+                        Ok(String::new())
+                    }
+                }
+            }
+            "genmove" => {
+                let color = parse_color(arg(args, 0)?)?;
+                self.give_turn(color);
+                let board = self.game.board_state().clone();
+                let vertex = match self.engine.select_move(&board, color) {
+                    Some(pos) if self.game.make_move(pos).is_ok() => Some(pos),
+                    _ => {
+                        self.game.pass();
+                        None
+                    }
+                };
+                if self.game.is_game_over().is_none() {
+                    self.engine
+                        .ponder(self.game.board_state(), self.game.current_player());
+                }
+                Ok(format_vertex(vertex, self.game.board_size()))
+            }
+            "showboard" => Ok(format!("\n{}", show_board(self.game.board_state()))),
+            "final_score" => {
+                let board = self.game.board_state();
+                let estimator = MonteCarloEstimator {
+                    playouts: 16,
+                    seed: seed::derive(self.seed, SCORING_STREAM),
+                };
+                let ownership = estimator.estimate(board, self.game.current_player());
+                let score = self.game.score(&dead_stones(board, &ownership, 0.5));
+                Ok(match score.winner() {
+                    _ if score.margin() == 0.0 => "0".to_string(),
+                    Stone::Black => format!("B+{}", score.margin()),
+                    Stone::White => format!("W+{}", score.margin()),
+                })
+            }
+            // Engine options take effect on a fresh engine, dropping the tree.
+            "betago-ponder" => {
+                self.config.ponder = parse_switch(arg(args, 0)?)?;
+                self.new_engine();
+                Ok(String::new())
+            }
+            "betago-reuse_tree" => {
+                self.config.reuse_tree = parse_switch(arg(args, 0)?)?;
+                self.new_engine();
+                Ok(String::new())
+            }
+            "betago-playouts" => {
+                self.config.playouts = arg(args, 0)?.parse().map_err(|_| "syntax error")?;
+                self.new_engine();
+                Ok(String::new())
+            }
+            _ => Err("unknown command".to_string()),
+        }
+    }
+
+    /// Hands the move to `color`. GTP lets either colour play at any time
+    /// and leaves ending the game to the controller, so passes never end it
+    /// here.
+    fn give_turn(&mut self, color: Stone) {
+        self.game.resume();
+        self.game.set_current_player(color);
+    }
+
+    /// Whether `color` may play `pos`, checked before `give_turn` so that an
+    /// illegal move leaves the side to move as it was.
+    fn is_legal(&self, pos: Position, color: Stone) -> bool {
+        let mut board = self.game.board_state().clone();
+        board.play(pos, color).is_ok()
+    }
+
+    /// A fresh engine with an empty tree, seeded from a per-engine stream.
+    fn new_engine(&mut self) {
+        self.engines += 1;
+        self.engine = build_engine(
+            self.kind,
+            self.weights.as_ref(),
+            self.config,
+            self.game.board_size(),
+            seed::derive(self.seed, self.engines),
+        );
+    }
+}
+
+fn build_engine(
+    kind: Engine,
+    weights: Option<&Arc<Network>>,
+    config: MctsConfig,
+    board_size: usize,
+    seed: u64,
+) -> Box<dyn GoAI> {
+    match kind {
+        Engine::Mcts => Box::new(MctsAI::new(config, seed)),
+        Engine::Network => {
+            let network = match weights {
+                Some(network) => Arc::clone(network),
+                None => Arc::new(Network::random(Architecture::new(board_size), seed)),
+            };
+            Box::new(NetworkAI::new(network, config, seed))
+        }
+    }
+}
+
+fn arg<'a>(args: &[&'a str], index: usize) -> Result<&'a str, String> {
+    args.get(index)
+        .copied()
+        .ok_or_else(|| "syntax error".to_string())
+}
+
+fn parse_color(word: &str) -> Result<Stone, String> {
+    match word.to_ascii_lowercase().as_str() {
+        "b" | "black" => Ok(Stone::Black),
+        "w" | "white" => Ok(Stone::White),
+        _ => Err("syntax error".to_string()),
+    }
+}
+
+fn parse_switch(word: &str) -> Result<bool, String> {
+    match word {
+        "on" | "true" | "1" => Ok(true),
+        "off" | "false" | "0" => Ok(false),
+        _ => Err("syntax error".to_string()),
+    }
+}
+
+/// Reads a GTP vertex such as `D4` (column letters skip `I`, rows count from
+/// the bottom) or `pass`.
+pub fn parse_vertex(word: &str, size: usize) -> Result<Option<Position>, String> {
+    if word.eq_ignore_ascii_case("pass") {
+        return Ok(None);
+    }
+    let mut chars = word.chars();
+    let column = chars
+        .next()
+        .and_then(|c| COLUMNS.find(c.to_ascii_uppercase()))
+        .ok_or("invalid coordinate")?;
+    let row: usize = chars.as_str().parse().map_err(|_| "invalid coordinate")?;
+    if column >= size || row == 0 || row > size {
+        return Err("invalid coordinate".to_string());
+    }
+    Ok(Some(Position {
+        x: column,
+        y: size - row,
+    }))
+}
+
+pub fn format_vertex(vertex: Option<Position>, size: usize) -> String {
+    match vertex {
+        Some(pos) => format!("{}{}", &COLUMNS[pos.x..=pos.x], size - pos.y),
+        None => "pass".to_string(),
+    }
+}
+
+fn show_board(board: &Board) -> String {
+    let size = board.size();
+    let mut text = String::new();
+    for y in 0..size {
+        text += &format!("{:>2} ", size - y);
+        for x in 0..size {
+            text += match board.get_stone(Position { x, y }) {
+                Ok(Some(Stone::Black)) => "X ",
+                Ok(Some(Stone::White)) => "O ",
+                _ => ". ",
+            };
+        }
+        text.push('\n');
+    }
+    text += "   ";
+    for x in 0..size {
+        text += &format!("{} ", &COLUMNS[x..=x]);
+    }
+    text
+}
@@ -1,52 +1,160 @@
 use crate::{
-    ai::{GoAI, heuristic::HeuristicAI, random::RandomAI},
-    core::{Stone, game::Game},
+    ai::{
+        GoAI,
+        ownership::{InfluenceEstimator, MonteCarloEstimator, OwnershipEstimator, dead_stones},
+    },
+    core::{
+        Board, Position, Score, Stone,
+        game::{Game, Move},
+        seed,
+    },
+    gui::clock::{Clock, format_duration},
+    gui::markers::{self, Markers},
+    gui::overlay::{self, Analysis, Overlays},
+    gui::review::Review,
+    gui::settings::Settings,
+    sgf::GameTree,
 };
 use eframe::egui;
+use std::thread::JoinHandle;
+
+const BLACK_STREAM: u64 = 1;
+const WHITE_STREAM: u64 = 2;
+const SCORING_STREAM: u64 = 3;
+
+type Engine = Box<dyn GoAI + Send>;
+
+/// An engine choosing its move on a worker thread, so that long searches
+/// don't freeze the window. The thread hands the engine back with the move.
+struct Thinking {
+    player: Stone,
+    thread: JoinHandle<(Engine, Option<Position>)>,
+}
 
 pub struct GoApp {
     game: Game,
-    board_size: f32,
+    clock: Clock,
+
+    settings: Settings,
+    seed: u64,
+    draft_settings: Option<Settings>,
+    settings_error: Option<String>,
+    black_ai: Option<Engine>,
+    white_ai: Option<Engine>,
+    thinking: Option<Thinking>,
 
-    black_ai: Box<dyn GoAI>,
-    white_ai: Box<dyn GoAI>,
+    overlays: Overlays,
+    markers: Markers,
+    analysis: Option<Analysis>,
+    final_score: Option<Score>,
+
+    review: Option<Review>,
+    sgf_path: String,
+    load_error: Option<String>,
 }
 
 impl Default for GoApp {
     fn default() -> Self {
-        Self {
-            game: Game::new(19),
-            board_size: 800.0,
-            black_ai: Box::new(RandomAI {}),
-            white_ai: Box::new(HeuristicAI {}),
-        }
+        let (settings, settings_error) = match Settings::load() {
+            Ok(settings) => (settings, None),
+            Err(e) => (Settings::default(), Some(e.to_string())),
+        };
+        let seed = settings.seed.unwrap_or_else(rand::random);
+        let mut app = Self {
+            game: settings.new_game(),
+            clock: Clock::default(),
+            black_ai: None,
+            white_ai: None,
+            thinking: None,
+            settings,
+            seed,
+            draft_settings: None,
+            settings_error,
+            overlays: Overlays::default(),
+            markers: Markers::default(),
+            analysis: None,
+            final_score: None,
+            review: None,
+            sgf_path: String::new(),
+            load_error: None,
+        };
+        app.build_engines();
+        app
     }
 }
 
 impl eframe::App for GoApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         ctx.request_repaint();
-        if self.game.is_game_over().is_none() {
-            let ai_move = match self.game.current_player() {
-                Stone::Black => self
-                    .black_ai
-                    .select_move(self.game.board_state(), Stone::Black),
-                Stone::White => self
-                    .white_ai
-                    .select_move(self.game.board_state(), Stone::White),
+        if let Some(review) = &mut self.review {
+            review.handle_keys(ctx);
+        } else if self.game.is_game_over().is_none() {
+            self.play_engine_move();
+            self.clock.run(self.game.current_player());
+        } else if self.final_score.is_none() {
+            self.clock.stop();
+            for ai in [&mut self.black_ai, &mut self.white_ai]
+                .into_iter()
+                .flatten()
+            {
+                ai.stop_pondering();
+            }
+            let board = self.game.board_state();
+            let estimator = MonteCarloEstimator {
+                playouts: 16,
+                seed: seed::derive(self.seed, SCORING_STREAM),
             };
+            let ownership = estimator.estimate(board, self.game.current_player());
+            let dead = dead_stones(board, &ownership, 0.5);
+            self.final_score = Some(self.game.score(&dead));
+            self.analysis = None;
+        }
 
-            match ai_move {
-                Some(pos) => match self.game.make_move(pos) {
-                    Ok(_) => {}
-                    Err(_) => self.game.pass(),
-                },
-                None => self.game.pass(),
-            }
-        } else {
-            self.game.reset();
+        egui::TopBottomPanel::top("overlays").show(ctx, |ui| {
+            ui.horizontal(|ui| self.markers.show_toggles(ui));
+            ui.horizontal(|ui| {
+                if self.review.is_some() {
+                    if ui.button("Back to game").clicked() {
+                        self.review = None;
+                    }
+                    return;
+                }
+
+                let before = self.overlays;
+                self.overlays.show_toggles(ui);
+                if self.overlays != before {
+                    self.analysis = None;
+                }
+
+                if self.final_score.is_none() && self.is_human_turn() && ui.button("Pass").clicked()
+                {
+                    self.game.pass();
+                }
+                if let Some(score) = &self.final_score {
+                    ui.label(format!("B {:.1} : W {:.1}", score.black, score.white));
+                }
+                if ui.button("New game").clicked() {
+                    self.draft_settings = Some(self.settings.clone());
+                }
+            });
+            ui.horizontal(|ui| self.show_review_loader(ui));
+        });
+
+        self.show_settings(ctx);
+
+        if let Some(review) = &mut self.review {
+            egui::TopBottomPanel::bottom("review").show(ctx, |ui| review.show_controls(ui));
+        } else if self.overlays.any() {
+            self.update_analysis();
         }
 
+        egui::SidePanel::right("game_info")
+            .resizable(false)
+            .show(ctx, |ui| self.show_game_info(ui));
+        egui::SidePanel::left("move_list")
+            .default_width(140.0)
+            .show(ctx, |ui| self.show_move_list(ui));
+
         egui::CentralPanel::default().show(ctx, |ui| {
             self.draw_board(ui);
         });
@@ -54,75 +162,482 @@ impl eframe::App for GoApp {
 }
 
 impl GoApp {
-    fn draw_board(&mut self, ui: &mut egui::Ui) {
-        let (response, painter) = ui.allocate_painter(
-            egui::vec2(self.board_size, self.board_size),
-            egui::Sense::click(),
-        );
+    fn ai(&self, player: Stone) -> Option<&(dyn GoAI + Send)> {
+        match player {
+            Stone::Black => self.black_ai.as_deref(),
+            Stone::White => self.white_ai.as_deref(),
+        }
+    }
+
+    fn ai_slot(&mut self, player: Stone) -> &mut Option<Engine> {
+        match player {
+            Stone::Black => &mut self.black_ai,
+            Stone::White => &mut self.white_ai,
+        }
+    }
+
+    /// Whether the side to move is left to the human. An engine that is
+    /// thinking is out of its slot, so the worker counts as well.
+    fn is_human_turn(&self) -> bool {
+        self.thinking.is_none() && self.ai(self.game.current_player()).is_none()
+    }
+
+    /// Starts the engine of the side to move on a worker thread, or plays
+    /// its move once the worker has finished, and lets it ponder.
+    fn play_engine_move(&mut self) {
+        if let Some(thinking) = self.thinking.take_if(|t| t.thread.is_finished()) {
+            let (mut ai, mv) = match thinking.thread.join() {
+                Ok(result) => result,
+                Err(panic) => std::panic::resume_unwind(panic),
+            };
+            match mv {
+                Some(pos) if self.game.make_move(pos).is_ok() => {}
+                _ => self.game.pass(),
+            }
+            if self.game.is_game_over().is_none() {
+                ai.ponder(self.game.board_state(), self.game.current_player());
+            }
+            *self.ai_slot(thinking.player) = Some(ai);
+        }
+        if self.thinking.is_some() || self.game.is_game_over().is_some() {
+            return;
+        }
+
+        let player = self.game.current_player();
+        let Some(mut ai) = self.ai_slot(player).take() else {
+            return;
+        };
+        let board = self.game.board_state().clone();
+        let thread = std::thread::spawn(move || {
+            let mv = ai.select_move(&board, player);
+            (ai, mv)
+        });
+        self.thinking = Some(Thinking { player, thread });
+    }
+
+    fn show_settings(&mut self, ctx: &egui::Context) {
+        let Some(draft) = &mut self.draft_settings else {
+            return;
+        };
+
+        let mut start = false;
+        let mut cancel = false;
+        egui::Window::new("New game")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                draft.show(ui);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    start = ui.button("Start").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+                if let Some(error) = &self.settings_error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            });
+
+        if cancel {
+            self.draft_settings = None;
+        } else if start && let Some(settings) = self.draft_settings.take() {
+            self.settings_error = settings.save().err().map(|e| e.to_string());
+            self.settings = settings;
+            self.start_game();
+        }
+    }
+
+    fn start_game(&mut self) {
+        // A search still running belongs to the old game; its thread is
+        // left to finish on its own and its engine is dropped.
+        self.thinking = None;
+        self.seed = self.settings.seed.unwrap_or_else(rand::random);
+        self.game = self.settings.new_game();
+        self.build_engines();
+        self.clock.reset();
+        self.review = None;
+        self.final_score = None;
+        self.analysis = None;
+    }
+
+    /// Builds both engines for the current settings. A side whose engine
+    /// cannot be built is left to the human and the settings window opens
+    /// with the reason.
+    fn build_engines(&mut self) {
+        let mut errors = Vec::new();
+        for (player, stream) in [(Stone::Black, BLACK_STREAM), (Stone::White, WHITE_STREAM)] {
+            let engine = match player {
+                Stone::Black => &self.settings.black,
+                Stone::White => &self.settings.white,
+            };
+            let ai = engine
+                .build(
+                    self.settings.board_size,
+                    self.settings.komi,
+                    seed::derive(self.seed, stream),
+                )
+                .unwrap_or_else(|e| {
+                    errors.push(format!("{player:?}: {e}"));
+                    None
+                });
+            match player {
+                Stone::Black => self.black_ai = ai,
+                Stone::White => self.white_ai = ai,
+            }
+        }
+        if !errors.is_empty() {
+            self.settings_error = Some(errors.join("\n"));
+            self.draft_settings = Some(self.settings.clone());
+        }
+    }
 
-        if response.clicked() {
-            if let Some(pos) = response.interact_pointer_pos() {
-                self.handle_click(pos, response.rect);
+    fn show_game_info(&self, ui: &mut egui::Ui) {
+        ui.heading("Game");
+        ui.label(format!("Board: {0}x{0}", self.displayed_board().size()));
+        ui.label(format!("Rules: {:?}", self.game.rules()));
+        ui.label(format!("Komi: {}", self.game.komi()));
+        ui.label(format!("Seed: {}", self.seed));
+        ui.label(format!(
+            "To play: {}",
+            match self.to_play() {
+                Stone::Black => "Black",
+                Stone::White => "White",
             }
+        ));
+        ui.separator();
+
+        ui.heading("Clock");
+        ui.monospace(format!(
+            "Black {}",
+            format_duration(self.clock.used(Stone::Black))
+        ));
+        ui.monospace(format!(
+            "White {}",
+            format_duration(self.clock.used(Stone::White))
+        ));
+        ui.separator();
+
+        let captures = match &self.review {
+            Some(review) => review.captures(),
+            None => self.game.captured_stones(),
+        };
+        ui.heading("Captures");
+        ui.label(format!("Black: {}", captures.0));
+        ui.label(format!("White: {}", captures.1));
+    }
+
+    fn show_move_list(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Moves");
+        let size = self.displayed_board().size();
+        let moves: Vec<(Option<usize>, Move)> = match &self.review {
+            Some(review) => review
+                .moves()
+                .into_iter()
+                .map(|(id, mv)| (Some(id), mv))
+                .collect(),
+            None => self.game.moves().iter().map(|&mv| (None, mv)).collect(),
+        };
+        let current = self.review.as_ref().map(Review::current);
+
+        egui::ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for (number, (id, mv)) in moves.into_iter().enumerate() {
+                    let color = match mv.stone() {
+                        Stone::Black => "B",
+                        Stone::White => "W",
+                    };
+                    let point = match mv.position() {
+                        Some(pos) => markers::coordinate_label(pos, size),
+                        None => "pass".to_string(),
+                    };
+                    let text = format!("{:>3}. {color} {point}", number + 1);
+                    let selected = id.is_some() && id == current;
+                    if ui.selectable_label(selected, text).clicked()
+                        && let (Some(review), Some(id)) = (&mut self.review, id)
+                    {
+                        review.go_to(id);
+                    }
+                }
+            });
+    }
+
+    fn draw_board(&mut self, ui: &mut egui::Ui) {
+        let available = ui.available_rect_before_wrap();
+        let side = available.width().min(available.height());
+        let rect = egui::Rect::from_center_size(available.center(), egui::vec2(side, side));
+        let response = ui.allocate_rect(rect, egui::Sense::click());
+        let painter = ui.painter_at(rect);
+
+        if response.clicked()
+            && let Some(pos) = response.interact_pointer_pos()
+        {
+            self.handle_click(pos, response.rect);
         }
 
-        painter.rect_filled(response.rect, 25.0, egui::Color32::from_rgb(210, 180, 130));
+        painter.rect_filled(
+            response.rect,
+            side * 0.03,
+            egui::Color32::from_rgb(210, 180, 130),
+        );
 
         self.draw_grid(&painter, response.rect);
+        if self.markers.coordinates {
+            markers::draw_coordinates(
+                &painter,
+                response.rect,
+                self.cell_size(response.rect),
+                self.displayed_board().size(),
+                egui::Color32::from_rgb(60, 40, 20),
+            );
+        }
 
         self.draw_stones(&painter, response.rect);
+
+        if self.overlays.any() && self.review.is_none() {
+            self.draw_overlays(&painter, response.rect);
+        }
+
+        if self.markers.hover
+            && let Some(pointer) = response.hover_pos()
+            && let Some(pos) = self.point_at(response.rect, pointer)
+            && let Ok(None) = self.displayed_board().get_stone(pos)
+        {
+            markers::draw_preview(
+                &painter,
+                self.point_center(response.rect, pos),
+                self.cell_size(response.rect),
+                self.to_play(),
+            );
+        }
+    }
+
+    fn to_play(&self) -> Stone {
+        match &self.review {
+            Some(review) => review.to_play(),
+            None => self.game.current_player(),
+        }
     }
 
-    fn handle_click(&mut self, pos: egui::Pos2, rect: egui::Rect) {
-        let board_size = (self.game.board_size() + 2) as f32;
-        let cell_size = rect.width() / (board_size - 1.0);
+    fn last_move(&self) -> Option<Position> {
+        match &self.review {
+            Some(review) => review.last_move(),
+            None => self.game.last_move(),
+        }
+    }
+
+    fn move_number(&self, pos: Position) -> Option<usize> {
+        match &self.review {
+            Some(review) => review.move_number(pos),
+            None => self.game.move_number(pos),
+        }
+    }
+
+    fn show_review_loader(&mut self, ui: &mut egui::Ui) {
+        if self.review.is_some() {
+            return;
+        }
+
+        if ui
+            .add_enabled(
+                !self.game.moves().is_empty(),
+                egui::Button::new("Review game"),
+            )
+            .clicked()
+        {
+            self.review = Some(Review::new(GameTree::from_game(&self.game)));
+        }
 
-        let x = ((pos.x - rect.left()) / cell_size).round() as usize;
-        let y = ((pos.y - rect.top()) / cell_size).round() as usize;
+        ui.add(egui::TextEdit::singleline(&mut self.sgf_path).hint_text("path/to/game.sgf"));
+        if ui.button("Load SGF").clicked() {
+            match GameTree::load(&self.sgf_path) {
+                Ok(tree) => {
+                    self.review = Some(Review::new(tree));
+                    self.load_error = None;
+                }
+                Err(e) => self.load_error = Some(e.to_string()),
+            }
+        }
+        if let Some(error) = &self.load_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
 
-        if x < board_size as usize && y < board_size as usize && x > 0 && y > 0 {
-            let _ = self.game.make_move(crate::Position { x: x - 1, y: y - 1 });
+    fn displayed_board(&self) -> &Board {
+        match &self.review {
+            Some(review) => review.board(),
+            None => self.game.board_state(),
+        }
+    }
+
+    fn update_analysis(&mut self) {
+        let board = self.game.board_state();
+        let player = self.game.current_player();
+        let move_number = self.game.moves().len();
+        if self
+            .analysis
+            .as_ref()
+            .is_some_and(|analysis| analysis.is_current(board.hash(), player, move_number))
+        {
+            return;
+        }
+
+        let ownership = InfluenceEstimator::default().estimate(board, player);
+        let score = match &self.final_score {
+            Some(score) => score.clone(),
+            None => self.game.score(&[]),
+        };
+        let wants_heatmap = self.overlays.move_probabilities && self.final_score.is_none();
+        let move_probabilities = if wants_heatmap {
+            overlay::heatmap(
+                board,
+                player,
+                self.ai(player).map(|ai| ai as &dyn GoAI),
+                self.ai(player.opposite()).map(|ai| ai as &dyn GoAI),
+            )
+        } else {
+            None
+        };
+        // An engine thinking on the worker thread is out of its slot.
+        let heatmap_pending =
+            wants_heatmap && move_probabilities.is_none() && self.thinking.is_some();
+
+        self.analysis = Some(Analysis {
+            hash: board.hash(),
+            to_play: player,
+            move_number,
+            ownership,
+            score,
+            move_probabilities,
+            heatmap_pending,
+        });
+    }
+
+    fn draw_overlays(&self, painter: &egui::Painter, rect: egui::Rect) {
+        let Some(analysis) = &self.analysis else {
+            return;
+        };
+        let cell_size = self.cell_size(rect);
+        let max_probability = analysis
+            .move_probabilities
+            .as_ref()
+            .map(|p| p.iter().copied().fold(0.0, f32::max))
+            .unwrap_or(0.0);
+
+        for y in 0..self.game.board_size() {
+            for x in 0..self.game.board_size() {
+                let pos = Position { x, y };
+                let idx = self.game.board_state().pos_to_index(pos);
+                let center = self.point_center(rect, pos);
+
+                if self.overlays.ownership {
+                    overlay::draw_ownership(painter, center, cell_size, analysis.ownership[idx]);
+                }
+                if self.overlays.territory
+                    && let Some(owner) = overlay::territory_owner(&analysis.score, pos)
+                {
+                    overlay::draw_territory(painter, center, cell_size, owner);
+                }
+                if self.overlays.dead_stones && analysis.score.dead.contains(&pos) {
+                    overlay::draw_dead_stone(painter, center, cell_size);
+                }
+                if self.overlays.move_probabilities
+                    && let Some(probabilities) = &analysis.move_probabilities
+                    && max_probability > 0.0
+                {
+                    overlay::draw_probability(
+                        painter,
+                        center,
+                        cell_size,
+                        probabilities[idx] / max_probability,
+                    );
+                }
+            }
+        }
+    }
+
+    fn cell_size(&self, rect: egui::Rect) -> f32 {
+        let board_size = (self.displayed_board().size() + 2) as f32;
+        rect.width() / (board_size - 1.0)
+    }
+
+    fn point_center(&self, rect: egui::Rect, pos: Position) -> egui::Pos2 {
+        let cell_size = self.cell_size(rect);
+        egui::Pos2::new(
+            rect.left() + (pos.x + 1) as f32 * cell_size,
+            rect.top() + (pos.y + 1) as f32 * cell_size,
+        )
+    }
+
+    /// Maps a screen position to the nearest intersection, if it lies on the
+    /// board.
+    fn point_at(&self, rect: egui::Rect, pointer: egui::Pos2) -> Option<Position> {
+        let cell_size = self.cell_size(rect);
+        let size = self.displayed_board().size() as isize;
+
+        let x = ((pointer.x - rect.left()) / cell_size).round() as isize - 1;
+        let y = ((pointer.y - rect.top()) / cell_size).round() as isize - 1;
+
+        ((0..size).contains(&x) && (0..size).contains(&y)).then_some(Position {
+            x: x as usize,
+            y: y as usize,
+        })
+    }
+
+    fn handle_click(&mut self, pointer: egui::Pos2, rect: egui::Rect) {
+        let Some(pos) = self.point_at(rect, pointer) else {
+            return;
+        };
+        let human_turn = self.is_human_turn();
+        match &mut self.review {
+            Some(review) => review.play(pos),
+            None if human_turn => {
+                let _ = self.game.make_move(pos);
+            }
+            None => {}
         }
     }
 
     fn draw_grid(&self, painter: &egui::Painter, rect: egui::Rect) {
-        let board_size = (self.game.board_size() + 2) as f32;
-        let cell_size = rect.width() / (board_size - 1.0);
+        let size = self.displayed_board().size();
+        let cell_size = self.cell_size(rect);
+        let stroke = egui::Stroke::new(line_width(cell_size), egui::Color32::BLACK);
+        let first = cell_size;
+        let last = size as f32 * cell_size;
 
-        for i in 0..self.game.board_size() + 1 {
-            let pos = i as f32 * cell_size;
+        for i in 0..size {
+            let pos = (i + 1) as f32 * cell_size;
             painter.line_segment(
                 [
-                    egui::Pos2::new(rect.left(), rect.top() + pos),
-                    egui::Pos2::new(rect.right(), rect.top() + pos),
+                    egui::Pos2::new(rect.left() + first, rect.top() + pos),
+                    egui::Pos2::new(rect.left() + last, rect.top() + pos),
                 ],
-                egui::Stroke::new(1.0, egui::Color32::BLACK),
+                stroke,
             );
 
             painter.line_segment(
                 [
-                    egui::Pos2::new(rect.left() + pos, rect.top()),
-                    egui::Pos2::new(rect.left() + pos, rect.bottom()),
+                    egui::Pos2::new(rect.left() + pos, rect.top() + first),
+                    egui::Pos2::new(rect.left() + pos, rect.top() + last),
                 ],
-                egui::Stroke::new(1.0, egui::Color32::BLACK),
+                stroke,
             );
         }
+
+        for pos in markers::hoshi_points(size) {
+            markers::draw_hoshi(painter, self.point_center(rect, pos), cell_size);
+        }
     }
 
     fn draw_stones(&self, painter: &egui::Painter, rect: egui::Rect) {
-        let board_size = (self.game.board_size() + 2) as f32;
-        let cell_size = rect.width() / (board_size - 1.0);
+        let cell_size = self.cell_size(rect);
         let stone_radius = cell_size * 0.4;
 
-        let board_state = self.game.board_state();
+        let board_state = self.displayed_board();
 
-        for y in 0..self.game.board_size() {
-            for x in 0..self.game.board_size() {
-                if let Ok(Some(stone)) = board_state.get_stone(crate::Position { x, y }) {
-                    let pos = egui::Pos2::new(
-                        rect.left() + (x + 1) as f32 * cell_size,
-                        rect.top() + (y + 1) as f32 * cell_size,
-                    );
+        for y in 0..board_state.size() {
+            for x in 0..board_state.size() {
+                if let Ok(Some(stone)) = board_state.get_stone(Position { x, y }) {
+                    let pos = self.point_center(rect, Position { x, y });
 
                     match stone {
                         Stone::Black => {
@@ -133,12 +648,38 @@ impl GoApp {
                             painter.circle_stroke(
                                 pos,
                                 stone_radius,
-                                egui::Stroke::new(1.0, egui::Color32::BLACK),
+                                egui::Stroke::new(line_width(cell_size), egui::Color32::BLACK),
                             );
                         }
                     }
+
+                    let number = self.move_number(Position { x, y });
+                    if self.markers.move_numbers
+                        && let Some(number) = number
+                    {
+                        markers::draw_move_number(painter, pos, cell_size, stone, number);
+                    } else if self.markers.last_move && self.last_move() == Some(Position { x, y })
+                    {
+                        markers::draw_last_move(painter, pos, cell_size, stone);
+                    }
                 }
             }
         }
+
+        if let Some(review) = &self.review {
+            for &captured in review.captured() {
+                painter.circle_stroke(
+                    self.point_center(rect, captured),
+                    stone_radius * 0.5,
+                    egui::Stroke::new(2.0 * line_width(cell_size), egui::Color32::RED),
+                );
+            }
+        }
     }
 }
+
+/// Grid and outline width that keeps the board legible from 9x9 on a large
+/// window down to 19x19 in a small one.
+fn line_width(cell_size: f32) -> f32 {
+    (cell_size * 0.03).clamp(0.5, 2.0)
+}
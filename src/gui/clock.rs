@@ -0,0 +1,53 @@
+use crate::core::Stone;
+use std::time::{Duration, Instant};
+
+/// Wall-clock time used by each side, charged to whoever is to move.
+#[derive(Debug, Default)]
+pub struct Clock {
+    black: Duration,
+    white: Duration,
+    running: Option<(Stone, Instant)>,
+}
+
+impl Clock {
+    pub fn run(&mut self, to_play: Stone) {
+        let now = Instant::now();
+        if let Some((stone, since)) = self.running {
+            *self.used_mut(stone) += now - since;
+        }
+        self.running = Some((to_play, now));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some((stone, since)) = self.running.take() {
+            *self.used_mut(stone) += since.elapsed();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Clock::default();
+    }
+
+    pub fn used(&self, stone: Stone) -> Duration {
+        let base = match stone {
+            Stone::Black => self.black,
+            Stone::White => self.white,
+        };
+        match self.running {
+            Some((running, since)) if running == stone => base + since.elapsed(),
+            _ => base,
+        }
+    }
+
+    fn used_mut(&mut self, stone: Stone) -> &mut Duration {
+        match stone {
+            Stone::Black => &mut self.black,
+            Stone::White => &mut self.white,
+        }
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,12 @@
+use crate::ai::network::NetworkError;
+use crate::ai::patterns::PatternError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EngineError {
+    #[error("Failed to load network weights: {0}")]
+    Network(#[from] NetworkError),
+
+    #[error("Failed to load pattern priors: {0}")]
+    Patterns(#[from] PatternError),
+}
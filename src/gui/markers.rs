@@ -0,0 +1,147 @@
+use crate::core::{Position, Stone};
+use eframe::egui;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Markers {
+    pub coordinates: bool,
+    pub last_move: bool,
+    pub move_numbers: bool,
+    pub hover: bool,
+}
+
+impl Default for Markers {
+    fn default() -> Self {
+        Markers {
+            coordinates: true,
+            last_move: true,
+            move_numbers: false,
+            hover: true,
+        }
+    }
+}
+
+impl Markers {
+    pub fn show_toggles(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.coordinates, "Coordinates");
+        ui.checkbox(&mut self.last_move, "Last move");
+        ui.checkbox(&mut self.move_numbers, "Move numbers");
+        ui.checkbox(&mut self.hover, "Preview");
+    }
+}
+
+/// Formats a point the way Go players write it: columns A-T skipping I, rows
+/// counted from the bottom.
+pub fn coordinate_label(pos: Position, size: usize) -> String {
+    format!("{}{}", column_label(pos.x), size - pos.y)
+}
+
+pub fn column_label(x: usize) -> char {
+    (b'A' + x as u8 + if x >= 8 { 1 } else { 0 }) as char
+}
+
+pub fn hoshi_points(size: usize) -> Vec<Position> {
+    if size < 7 {
+        return Vec::new();
+    }
+
+    let edge = if size >= 13 { 3 } else { 2 };
+    let far = size - 1 - edge;
+    let mut lines = vec![edge, far];
+    if size % 2 == 1 {
+        lines.push(size / 2);
+    }
+
+    let mut points = Vec::new();
+    for &y in &lines {
+        for &x in &lines {
+            let side = (x == size / 2) != (y == size / 2);
+            if side && size < 19 {
+                continue;
+            }
+            points.push(Position { x, y });
+        }
+    }
+    points
+}
+
+pub fn draw_coordinates(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    cell_size: f32,
+    size: usize,
+    color: egui::Color32,
+) {
+    let font = egui::FontId::proportional(cell_size * 0.35);
+    let margin = cell_size * 0.45;
+
+    for i in 0..size {
+        let offset = (i + 1) as f32 * cell_size;
+        let column = column_label(i).to_string();
+        let row = (size - i).to_string();
+
+        for (anchor, text) in [
+            (
+                egui::pos2(rect.left() + offset, rect.top() + margin),
+                &column,
+            ),
+            (
+                egui::pos2(rect.left() + offset, rect.bottom() - margin),
+                &column,
+            ),
+            (egui::pos2(rect.left() + margin, rect.top() + offset), &row),
+            (egui::pos2(rect.right() - margin, rect.top() + offset), &row),
+        ] {
+            painter.text(
+                anchor,
+                egui::Align2::CENTER_CENTER,
+                text,
+                font.clone(),
+                color,
+            );
+        }
+    }
+}
+
+pub fn draw_hoshi(painter: &egui::Painter, center: egui::Pos2, cell_size: f32) {
+    painter.circle_filled(center, cell_size * 0.1, egui::Color32::BLACK);
+}
+
+pub fn draw_last_move(painter: &egui::Painter, center: egui::Pos2, cell_size: f32, stone: Stone) {
+    painter.circle_stroke(
+        center,
+        cell_size * 0.18,
+        egui::Stroke::new(2.0, contrast(stone)),
+    );
+}
+
+pub fn draw_move_number(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    cell_size: f32,
+    stone: Stone,
+    number: usize,
+) {
+    let scale = if number >= 100 { 0.3 } else { 0.4 };
+    painter.text(
+        center,
+        egui::Align2::CENTER_CENTER,
+        number.to_string(),
+        egui::FontId::proportional(cell_size * scale),
+        contrast(stone),
+    );
+}
+
+pub fn draw_preview(painter: &egui::Painter, center: egui::Pos2, cell_size: f32, stone: Stone) {
+    let color = match stone {
+        Stone::Black => egui::Color32::from_black_alpha(110),
+        Stone::White => egui::Color32::from_white_alpha(150),
+    };
+    painter.circle_filled(center, cell_size * 0.4, color);
+}
+
+fn contrast(stone: Stone) -> egui::Color32 {
+    match stone {
+        Stone::Black => egui::Color32::WHITE,
+        Stone::White => egui::Color32::BLACK,
+    }
+}
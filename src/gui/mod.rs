@@ -1 +1,7 @@
 pub mod app;
+pub mod clock;
+pub mod error;
+pub mod markers;
+pub mod overlay;
+pub mod review;
+pub mod settings;
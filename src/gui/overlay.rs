@@ -0,0 +1,137 @@
+use crate::ai::GoAI;
+use crate::core::{Board, Position, Score, Stone};
+use eframe::egui;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Overlays {
+    pub territory: bool,
+    pub ownership: bool,
+    pub dead_stones: bool,
+    pub move_probabilities: bool,
+}
+
+impl Overlays {
+    pub fn show_toggles(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.territory, "Territory");
+        ui.checkbox(&mut self.ownership, "Ownership");
+        ui.checkbox(&mut self.dead_stones, "Dead stones");
+        ui.checkbox(&mut self.move_probabilities, "AI heatmap");
+    }
+
+    pub fn any(&self) -> bool {
+        self.territory || self.ownership || self.dead_stones || self.move_probabilities
+    }
+}
+
+/// Everything the overlays need for one position, recomputed only when the
+/// board hash, the side to move or the move number changes. A pass keeps the
+/// hash but hands the move to the other side.
+pub struct Analysis {
+    pub hash: u64,
+    pub to_play: Stone,
+    pub move_number: usize,
+    pub ownership: Vec<f32>,
+    pub score: Score,
+    pub move_probabilities: Option<Vec<f32>>,
+    /// The engine for the heatmap was away thinking, so the analysis is
+    /// made again once it is back.
+    pub heatmap_pending: bool,
+}
+
+impl Analysis {
+    /// Whether the analysis still holds for the given position and can be
+    /// drawn as it is.
+    pub fn is_current(&self, hash: u64, to_play: Stone, move_number: usize) -> bool {
+        !self.heatmap_pending
+            && self.hash == hash
+            && self.to_play == to_play
+            && self.move_number == move_number
+    }
+}
+
+/// Move probabilities for `player` on `board`, from `player`'s own engine or
+/// else the opponent's, which can read the position for either side. Human
+/// against human games have no heatmap.
+pub fn heatmap(
+    board: &Board,
+    player: Stone,
+    own: Option<&dyn GoAI>,
+    opponent: Option<&dyn GoAI>,
+) -> Option<Vec<f32>> {
+    own.or(opponent)?.move_probabilities(board, player)
+}
+
+/// Shades a point by `value`. The square root lifts weak territory, which
+/// estimators keep well below the ±1 of stones, into view.
+pub fn draw_ownership(painter: &egui::Painter, center: egui::Pos2, cell_size: f32, value: f32) {
+    let alpha = (value.abs().min(1.0).sqrt() * 110.0) as u8;
+    let color = if value > 0.0 {
+        egui::Color32::from_black_alpha(alpha)
+    } else {
+        egui::Color32::from_white_alpha(alpha)
+    };
+    painter.rect_filled(
+        egui::Rect::from_center_size(center, egui::vec2(cell_size, cell_size)),
+        0.0,
+        color,
+    );
+}
+
+pub fn draw_territory(painter: &egui::Painter, center: egui::Pos2, cell_size: f32, owner: Stone) {
+    let color = match owner {
+        Stone::Black => egui::Color32::BLACK,
+        Stone::White => egui::Color32::WHITE,
+    };
+    let rect = egui::Rect::from_center_size(center, egui::vec2(cell_size, cell_size) * 0.3);
+    painter.rect_filled(rect, 0.0, color);
+    painter.rect_stroke(
+        rect,
+        0.0,
+        egui::Stroke::new(1.0, egui::Color32::DARK_GRAY),
+        egui::StrokeKind::Middle,
+    );
+}
+
+pub fn draw_dead_stone(painter: &egui::Painter, center: egui::Pos2, cell_size: f32) {
+    let board_color = egui::Color32::from_rgba_unmultiplied(210, 180, 130, 170);
+    painter.circle_filled(center, cell_size * 0.4, board_color);
+
+    let arm = cell_size * 0.15;
+    let stroke = egui::Stroke::new(2.0, egui::Color32::RED);
+    painter.line_segment(
+        [
+            center + egui::vec2(-arm, -arm),
+            center + egui::vec2(arm, arm),
+        ],
+        stroke,
+    );
+    painter.line_segment(
+        [
+            center + egui::vec2(-arm, arm),
+            center + egui::vec2(arm, -arm),
+        ],
+        stroke,
+    );
+}
+
+pub fn draw_probability(painter: &egui::Painter, center: egui::Pos2, cell_size: f32, weight: f32) {
+    if weight <= 0.0 {
+        return;
+    }
+    let alpha = (40.0 + weight.min(1.0) * 180.0) as u8;
+    painter.circle_filled(
+        center,
+        cell_size * (0.1 + 0.25 * weight.min(1.0)),
+        egui::Color32::from_rgba_unmultiplied(220, 40, 40, alpha),
+    );
+}
+
+pub fn territory_owner(score: &Score, pos: Position) -> Option<Stone> {
+    if score.black_territory.contains(&pos) {
+        Some(Stone::Black)
+    } else if score.white_territory.contains(&pos) {
+        Some(Stone::White)
+    } else {
+        None
+    }
+}
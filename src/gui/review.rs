@@ -0,0 +1,203 @@
+use crate::core::game::Move;
+use crate::core::{Board, Position, Stone};
+use crate::gui::markers::coordinate_label;
+use crate::sgf::{GameTree, NodeState};
+use eframe::egui;
+
+pub struct Review {
+    tree: GameTree,
+    current: usize,
+    state: NodeState,
+    error: Option<String>,
+}
+
+impl Review {
+    pub fn new(tree: GameTree) -> Self {
+        let mut review = Review {
+            current: tree.root(),
+            state: NodeState::new(tree.size),
+            tree,
+            error: None,
+        };
+        review.go_to(review.tree.root());
+        review
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.state.board
+    }
+
+    pub fn tree(&self) -> &GameTree {
+        &self.tree
+    }
+
+    pub fn move_number(&self, pos: Position) -> Option<usize> {
+        self.state.move_numbers[self.state.board.pos_to_index(pos)]
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn captures(&self) -> (usize, usize) {
+        self.state.captures
+    }
+
+    /// Moves from the root to the current node, paired with their node ids.
+    pub fn moves(&self) -> Vec<(usize, Move)> {
+        self.tree
+            .path_to(self.current)
+            .into_iter()
+            .filter_map(|id| self.tree.node(id).mv.map(|mv| (id, mv)))
+            .collect()
+    }
+
+    pub fn to_play(&self) -> Stone {
+        self.state.to_play
+    }
+
+    pub fn captured(&self) -> &[Position] {
+        &self.state.captured
+    }
+
+    pub fn last_move(&self) -> Option<Position> {
+        self.tree.node(self.current).mv.and_then(|mv| mv.position())
+    }
+
+    pub fn go_to(&mut self, id: usize) {
+        match self.tree.state_at(id) {
+            Ok(state) => {
+                self.state = state;
+                self.current = id;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    pub fn forward(&mut self) {
+        if let Some(&child) = self.tree.node(self.current).children.first() {
+            self.go_to(child);
+        }
+    }
+
+    pub fn back(&mut self) {
+        if let Some(parent) = self.tree.node(self.current).parent {
+            self.go_to(parent);
+        }
+    }
+
+    pub fn switch_variation(&mut self, step: isize) {
+        let siblings = self.tree.siblings(self.current);
+        let Some(index) = siblings.iter().position(|&id| id == self.current) else {
+            return;
+        };
+        let next = (index as isize + step).rem_euclid(siblings.len() as isize) as usize;
+        self.go_to(siblings[next]);
+    }
+
+    /// Plays `pos` for the side to move, adding a new branch when the move is
+    /// not already in the tree.
+    pub fn play(&mut self, pos: Position) {
+        let stone = self.state.to_play;
+        let mut board = self.state.board.clone();
+        match board.play(pos, stone) {
+            Ok(_) => {
+                let id = self.tree.add_child(self.current, Move::Play(stone, pos));
+                self.go_to(id);
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    pub fn handle_keys(&mut self, ctx: &egui::Context) {
+        let (left, right, up, down) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::ArrowLeft),
+                i.key_pressed(egui::Key::ArrowRight),
+                i.key_pressed(egui::Key::ArrowUp),
+                i.key_pressed(egui::Key::ArrowDown),
+            )
+        });
+
+        if left {
+            self.back();
+        }
+        if right {
+            self.forward();
+        }
+        if up {
+            self.switch_variation(-1);
+        }
+        if down {
+            self.switch_variation(1);
+        }
+    }
+
+    pub fn show_controls(&mut self, ui: &mut egui::Ui) {
+        let mut line = self.tree.path_to(self.current);
+        line.extend(self.tree.line_from(self.current).into_iter().skip(1));
+        let mut index = line
+            .iter()
+            .position(|&id| id == self.current)
+            .unwrap_or_default();
+
+        ui.horizontal(|ui| {
+            if ui.button("<").clicked() {
+                self.back();
+            }
+            let slider = egui::Slider::new(&mut index, 0..=line.len() - 1).text("Move");
+            if ui.add(slider).changed() {
+                self.go_to(line[index]);
+            }
+            if ui.button(">").clicked() {
+                self.forward();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Captures  B: {}  W: {}",
+                self.state.captures.0, self.state.captures.1
+            ));
+
+            let siblings = self.tree.siblings(self.current);
+            if siblings.len() > 1 {
+                let index = siblings
+                    .iter()
+                    .position(|&id| id == self.current)
+                    .unwrap_or(0);
+                ui.label(format!("Variation {}/{}", index + 1, siblings.len()));
+                if ui.button("Prev").clicked() {
+                    self.switch_variation(-1);
+                }
+                if ui.button("Next").clicked() {
+                    self.switch_variation(1);
+                }
+            }
+        });
+
+        let children = self.tree.node(self.current).children.clone();
+        if children.len() > 1 {
+            ui.horizontal(|ui| {
+                ui.label("Next moves:");
+                for child in children {
+                    let label = match self.tree.node(child).mv {
+                        Some(Move::Play(_, pos)) => coordinate_label(pos, self.tree.size),
+                        _ => "Pass".to_string(),
+                    };
+                    if ui.button(label).clicked() {
+                        self.go_to(child);
+                    }
+                }
+            });
+        }
+
+        if let Some(comment) = &self.tree.node(self.current).comment {
+            ui.label(comment);
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+}
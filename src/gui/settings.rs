@@ -0,0 +1,450 @@
+use crate::ai::mcts::{MctsAI, MctsConfig, Parallelism};
+use crate::ai::network::{Architecture, Network, NetworkAI};
+use crate::ai::patterns::PatternPriors;
+use crate::ai::{GoAI, heuristic::HeuristicAI, playout::PlayoutPolicy, random::RandomAI};
+use crate::config::{ConfigError, ConfigFile, config_path};
+use crate::core::{Rules, game::Game};
+use crate::gui::error::EngineError;
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const CONFIG_FILE: &str = "settings.conf";
+const STANDARD_SIZES: [usize; 3] = [9, 13, 19];
+const SIZE_RANGE: std::ops::RangeInclusive<usize> = 5..=25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineKind {
+    Human,
+    Random,
+    Heuristic,
+    Mcts,
+    Network,
+}
+
+impl EngineKind {
+    const ALL: [EngineKind; 5] = [
+        EngineKind::Human,
+        EngineKind::Random,
+        EngineKind::Heuristic,
+        EngineKind::Mcts,
+        EngineKind::Network,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            EngineKind::Human => "human",
+            EngineKind::Random => "random",
+            EngineKind::Heuristic => "heuristic",
+            EngineKind::Mcts => "mcts",
+            EngineKind::Network => "network",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineSettings {
+    pub kind: EngineKind,
+    pub playout: PlayoutPolicy,
+    pub heuristic: HeuristicAI,
+    pub mcts: MctsConfig,
+    /// Search settings of the network engine.
+    pub network: MctsConfig,
+    /// Weights for the network engine; untrained ones are used when unset.
+    pub weights: Option<PathBuf>,
+    /// Learned pattern priors for the heuristic and MCTS engines.
+    pub patterns: Option<PathBuf>,
+}
+
+impl EngineSettings {
+    fn new(kind: EngineKind) -> Self {
+        EngineSettings {
+            kind,
+            playout: PlayoutPolicy::default(),
+            heuristic: HeuristicAI::default(),
+            mcts: MctsConfig::default(),
+            network: MctsConfig {
+                playouts: 200,
+                ..MctsConfig::default()
+            },
+            weights: None,
+            patterns: None,
+        }
+    }
+
+    /// `None` for a human player. Without a weights file the network engine
+    /// gets untrained weights for a `board_size` board; a weights file that
+    /// cannot be loaded or was built for another size is an error, as is a
+    /// pattern file that cannot be loaded.
+    pub fn build(
+        &self,
+        board_size: usize,
+        komi: f32,
+        seed: u64,
+    ) -> Result<Option<Box<dyn GoAI + Send>>, EngineError> {
+        let patterns = match (&self.patterns, self.kind) {
+            (Some(path), EngineKind::Heuristic | EngineKind::Mcts) => {
+                Some(Arc::new(PatternPriors::load(path)?))
+            }
+            _ => None,
+        };
+        Ok(match self.kind {
+            EngineKind::Human => None,
+            EngineKind::Random => Some(Box::new(RandomAI::new(self.playout, seed))),
+            EngineKind::Heuristic => Some(Box::new(match patterns {
+                Some(patterns) => self
+                    .heuristic
+                    .clone()
+                    .with_patterns(patterns, self.heuristic.pattern_weight),
+                None => self.heuristic.clone(),
+            })),
+            EngineKind::Mcts => {
+                let ai = MctsAI::new(MctsConfig { komi, ..self.mcts }, seed);
+                Some(Box::new(match patterns {
+                    Some(patterns) => ai.with_patterns(patterns),
+                    None => ai,
+                }))
+            }
+            EngineKind::Network => {
+                let config = MctsConfig {
+                    komi,
+                    ..self.network
+                };
+                Some(Box::new(match &self.weights {
+                    Some(path) => NetworkAI::load(path, board_size, config, seed)?,
+                    None => NetworkAI::new(
+                        Arc::new(Network::random(Architecture::new(board_size), seed)),
+                        config,
+                        seed,
+                    ),
+                }))
+            }
+        })
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, label: &str) {
+        egui::ComboBox::from_label(label)
+            .selected_text(self.kind.name())
+            .show_ui(ui, |ui| {
+                for kind in EngineKind::ALL {
+                    ui.selectable_value(&mut self.kind, kind, kind.name());
+                }
+            });
+        match self.kind {
+            EngineKind::Human => {}
+            EngineKind::Random => {
+                ui.horizontal(|ui| {
+                    for (preset, name) in [
+                        (PlayoutPolicy::uniform(0.1), "Uniform"),
+                        (PlayoutPolicy::light(), "Light"),
+                        (PlayoutPolicy::heavy(), "Heavy"),
+                    ] {
+                        ui.selectable_value(&mut self.playout, preset, name);
+                    }
+                });
+                let policy = &mut self.playout;
+                ui.checkbox(&mut policy.avoid_eye_filling, "Never fill own eyes");
+                ui.checkbox(&mut policy.answer_atari, "Answer ataris");
+                ui.checkbox(&mut policy.prefer_captures, "Prefer captures");
+                ui.checkbox(&mut policy.pass_only_when_stuck, "Pass only when stuck");
+                if !policy.pass_only_when_stuck {
+                    ui.add(
+                        egui::Slider::new(&mut policy.pass_probability, 0.0..=1.0)
+                            .text("Pass probability"),
+                    );
+                }
+            }
+            EngineKind::Heuristic => {
+                show_path(ui, "Patterns", &mut self.patterns, "none");
+                let weights = [
+                    (&mut self.heuristic.capture_weight, "Capture"),
+                    (&mut self.heuristic.save_weight, "Save"),
+                    (&mut self.heuristic.attack_weight, "Attack"),
+                    (&mut self.heuristic.expand_weight, "Expand"),
+                    (&mut self.heuristic.eye_weight, "Eye"),
+                ];
+                for (weight, label) in weights {
+                    ui.add(egui::Slider::new(weight, 0.0..=20.0).text(label));
+                }
+                if self.patterns.is_some() {
+                    ui.add(
+                        egui::Slider::new(&mut self.heuristic.pattern_weight, 0.0..=4.0)
+                            .text("Patterns"),
+                    );
+                }
+            }
+            EngineKind::Mcts => {
+                show_path(ui, "Patterns", &mut self.patterns, "none");
+                let mcts = &mut self.mcts;
+                ui.add(
+                    egui::Slider::new(&mut mcts.playouts, 100..=20000)
+                        .logarithmic(true)
+                        .text("Playouts"),
+                );
+                ui.add(egui::Slider::new(&mut mcts.exploration, 0.1..=2.0).text("Exploration"));
+                ui.checkbox(&mut mcts.transpositions, "Share transpositions");
+                ui.checkbox(&mut mcts.rave, "RAVE");
+                if mcts.rave {
+                    ui.add(
+                        egui::Slider::new(&mut mcts.rave_equivalence, 10.0..=10000.0)
+                            .logarithmic(true)
+                            .text("RAVE equivalence"),
+                    );
+                }
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut mcts.policy, PlayoutPolicy::light(), "Light playouts");
+                    ui.selectable_value(&mut mcts.policy, PlayoutPolicy::heavy(), "Heavy playouts");
+                });
+                show_search_threads(ui, mcts);
+            }
+            EngineKind::Network => {
+                show_path(ui, "Weights", &mut self.weights, "untrained");
+                let network = &mut self.network;
+                ui.add(
+                    egui::Slider::new(&mut network.playouts, 10..=5000)
+                        .logarithmic(true)
+                        .text("Playouts"),
+                );
+                ui.add(egui::Slider::new(&mut network.c_puct, 0.1..=5.0).text("PUCT constant"));
+                ui.add(egui::Slider::new(&mut network.batch_size, 1..=64).text("Batch size"));
+                ui.add(egui::Slider::new(&mut network.temperature, 0.0..=2.0).text("Temperature"));
+                ui.add(
+                    egui::Slider::new(&mut network.noise_fraction, 0.0..=1.0).text("Root noise"),
+                );
+                if network.noise_fraction > 0.0 {
+                    ui.add(
+                        egui::Slider::new(&mut network.dirichlet_alpha, 0.01..=1.0)
+                            .logarithmic(true)
+                            .text("Dirichlet alpha"),
+                    );
+                }
+                show_search_threads(ui, network);
+            }
+        }
+    }
+
+    fn read(config: &ConfigFile, prefix: &str, default: EngineKind) -> Result<Self, ConfigError> {
+        let mut engine = EngineSettings::new(default);
+        let key = format!("{prefix}.engine");
+        if let Some(name) = config.raw(&key) {
+            engine.kind = EngineKind::from_name(name).ok_or_else(|| ConfigError::InvalidValue {
+                key,
+                value: name.to_string(),
+            })?;
+        }
+        engine.playout = engine.playout.from_config(config, &format!("{prefix}."))?;
+        engine.heuristic = HeuristicAI::from_config(config, &format!("{prefix}."))?;
+        engine.mcts = engine
+            .mcts
+            .from_config(config, &format!("{prefix}.mcts."))?;
+        engine.network = engine
+            .network
+            .from_config(config, &format!("{prefix}.network."))?;
+        engine.weights = config.raw(&format!("{prefix}.weights")).map(PathBuf::from);
+        engine.patterns = config.raw(&format!("{prefix}.patterns")).map(PathBuf::from);
+        Ok(engine)
+    }
+
+    fn write(&self, config: &mut ConfigFile, prefix: &str) {
+        config.set(&format!("{prefix}.engine"), self.kind.name());
+        self.playout.write_config(config, &format!("{prefix}."));
+        self.heuristic.write_config(config, &format!("{prefix}."));
+        self.mcts.write_config(config, &format!("{prefix}.mcts."));
+        self.network
+            .write_config(config, &format!("{prefix}.network."));
+        if let Some(weights) = &self.weights {
+            config.set(&format!("{prefix}.weights"), weights.display());
+        }
+        if let Some(patterns) = &self.patterns {
+            config.set(&format!("{prefix}.patterns"), patterns.display());
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub board_size: usize,
+    pub komi: f32,
+    pub rules: Rules,
+    pub handicap: usize,
+    pub black: EngineSettings,
+    pub white: EngineSettings,
+    /// Fixed game seed for reproducible games; a fresh one is drawn per game
+    /// when unset.
+    pub seed: Option<u64>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            board_size: 19,
+            komi: 6.5,
+            rules: Rules::default(),
+            handicap: 0,
+            black: EngineSettings::new(EngineKind::Random),
+            white: EngineSettings::new(EngineKind::Heuristic),
+            seed: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn path() -> PathBuf {
+        config_path(CONFIG_FILE)
+    }
+
+    pub fn load() -> Result<Self, ConfigError> {
+        match ConfigFile::load(Self::path()) {
+            Ok(config) => Self::from_config(&config),
+            Err(ConfigError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Settings::default())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.to_config().save(Self::path())
+    }
+
+    pub fn from_config(config: &ConfigFile) -> Result<Self, ConfigError> {
+        let mut settings = Settings::default();
+        if let Some(size) = config.get("board_size")? {
+            if !SIZE_RANGE.contains(&size) {
+                return Err(ConfigError::InvalidValue {
+                    key: "board_size".to_string(),
+                    value: size.to_string(),
+                });
+            }
+            settings.board_size = size;
+        }
+        if let Some(komi) = config.get("komi")? {
+            settings.komi = komi;
+        }
+        if let Some(rules) = config.raw("rules") {
+            settings.rules = match rules {
+                "japanese" => Rules::Japanese,
+                "chinese" => Rules::Chinese,
+                _ => {
+                    return Err(ConfigError::InvalidValue {
+                        key: "rules".to_string(),
+                        value: rules.to_string(),
+                    });
+                }
+            };
+        }
+        if let Some(handicap) = config.get("handicap")? {
+            settings.handicap = handicap;
+        }
+        settings.seed = match config.raw("seed") {
+            None | Some("random") => None,
+            Some(_) => config.get("seed")?,
+        };
+        settings.black = EngineSettings::read(config, "black", settings.black.kind)?;
+        settings.white = EngineSettings::read(config, "white", settings.white.kind)?;
+        Ok(settings)
+    }
+
+    pub fn to_config(&self) -> ConfigFile {
+        let mut config = ConfigFile::default();
+        config.set("board_size", self.board_size);
+        config.set("komi", self.komi);
+        config.set(
+            "rules",
+            match self.rules {
+                Rules::Japanese => "japanese",
+                Rules::Chinese => "chinese",
+            },
+        );
+        config.set("handicap", self.handicap);
+        match self.seed {
+            Some(seed) => config.set("seed", seed),
+            None => config.set("seed", "random"),
+        }
+        self.black.write(&mut config, "black");
+        self.white.write(&mut config, "white");
+        config
+    }
+
+    pub fn new_game(&self) -> Game {
+        Game::with_handicap(self.board_size, self.rules, self.komi, self.handicap)
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Board size");
+            for size in STANDARD_SIZES {
+                ui.radio_value(&mut self.board_size, size, format!("{size}x{size}"));
+            }
+            ui.add(egui::DragValue::new(&mut self.board_size).range(SIZE_RANGE));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Komi");
+            ui.add(
+                egui::DragValue::new(&mut self.komi)
+                    .speed(0.5)
+                    .range(-50.0..=50.0),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Rules");
+            ui.radio_value(&mut self.rules, Rules::Japanese, "Japanese");
+            ui.radio_value(&mut self.rules, Rules::Chinese, "Chinese");
+        });
+        ui.add(egui::Slider::new(&mut self.handicap, 0..=9).text("Handicap"));
+        ui.horizontal(|ui| {
+            let mut fixed = self.seed.is_some();
+            ui.checkbox(&mut fixed, "Fixed seed");
+            match (fixed, &mut self.seed) {
+                (true, Some(seed)) => {
+                    ui.add(egui::DragValue::new(seed));
+                }
+                (true, None) => self.seed = Some(0),
+                (false, _) => self.seed = None,
+            }
+        });
+        ui.separator();
+        self.black.show(ui, "Black");
+        self.white.show(ui, "White");
+    }
+}
+
+fn show_search_threads(ui: &mut egui::Ui, config: &mut MctsConfig) {
+    ui.checkbox(&mut config.reuse_tree, "Reuse the tree between moves");
+    ui.add_enabled(
+        config.reuse_tree,
+        egui::Checkbox::new(&mut config.ponder, "Ponder on the opponent's time"),
+    );
+    ui.add(egui::Slider::new(&mut config.threads, 1..=64).text("Threads"));
+    if config.threads > 1 {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut config.parallelism, Parallelism::Tree, "Shared tree");
+            ui.selectable_value(
+                &mut config.parallelism,
+                Parallelism::Root,
+                "Tree per thread",
+            );
+        });
+        if config.parallelism == Parallelism::Tree {
+            ui.add(egui::Slider::new(&mut config.virtual_loss, 0..=10).text("Virtual loss"));
+        }
+    }
+}
+
+fn show_path(ui: &mut egui::Ui, label: &str, path: &mut Option<PathBuf>, unset: &str) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut text = path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let edit = egui::TextEdit::singleline(&mut text).hint_text(unset);
+        if ui.add(edit).changed() {
+            *path = (!text.is_empty()).then(|| PathBuf::from(text));
+        }
+    });
+}
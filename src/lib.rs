@@ -1,5 +1,9 @@
 pub mod ai;
+pub mod config;
 pub mod core;
+pub mod gtp;
 pub mod gui;
+pub mod sgf;
+pub mod tactics;
 
 pub use core::{Board, GoError, Position, Stone};
@@ -3,8 +3,9 @@ use egui::{Vec2, ViewportBuilder};
 
 fn main() -> eframe::Result<()> {
     let viewport_builder = ViewportBuilder {
-        resizable: Some(false),
-        inner_size: Some(Vec2::new(410.0, 410.0)),
+        resizable: Some(true),
+        inner_size: Some(Vec2::new(1100.0, 820.0)),
+        min_inner_size: Some(Vec2::new(600.0, 420.0)),
         ..Default::default()
     };
     let native_options = eframe::NativeOptions {
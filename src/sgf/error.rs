@@ -0,0 +1,26 @@
+use crate::core::GoError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SgfError {
+    #[error("Unexpected end of SGF input")]
+    UnexpectedEnd,
+
+    #[error("Unexpected character {found:?} at offset {offset}")]
+    UnexpectedChar { found: char, offset: usize },
+
+    #[error("Invalid coordinate {value:?}")]
+    InvalidCoordinate { value: String },
+
+    #[error("Invalid board size {value:?}")]
+    InvalidSize { value: String },
+
+    #[error("Illegal setup stone in game record: {0}")]
+    IllegalSetup(#[source] GoError),
+
+    #[error("Illegal move in game record: {0}")]
+    IllegalMove(#[from] GoError),
+
+    #[error("Failed to read SGF file: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,24 @@
+pub mod error;
+mod parser;
+pub mod tree;
+
+pub use error::SgfError;
+pub use tree::{GameTree, NodeState, TreeNode};
+
+use std::path::{Path, PathBuf};
+
+/// `.sgf` files directly inside `dir`, sorted so that runs are reproducible.
+pub fn sgf_files(dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, SgfError> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("sgf"))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -0,0 +1,128 @@
+use crate::sgf::SgfError;
+
+pub(crate) type Properties = Vec<(String, Vec<String>)>;
+
+#[derive(Debug, Default)]
+pub(crate) struct RawTree {
+    pub nodes: Vec<Properties>,
+    pub variations: Vec<RawTree>,
+}
+
+pub(crate) fn parse_collection(input: &str) -> Result<Vec<RawTree>, SgfError> {
+    let mut parser = Parser {
+        chars: input.char_indices().collect(),
+        pos: 0,
+    };
+
+    let mut trees = Vec::new();
+    parser.skip_whitespace();
+    while parser.peek().is_some() {
+        trees.push(parser.tree()?);
+        parser.skip_whitespace();
+    }
+    Ok(trees)
+}
+
+struct Parser {
+    chars: Vec<(usize, char)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).map(|&(_, c)| c)
+    }
+
+    fn next(&mut self) -> Result<char, SgfError> {
+        let c = self.peek().ok_or(SgfError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SgfError> {
+        let offset = self.offset();
+        match self.next()? {
+            c if c == expected => Ok(()),
+            found => Err(SgfError::UnexpectedChar { found, offset }),
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.chars
+            .get(self.pos)
+            .map(|&(offset, _)| offset)
+            .unwrap_or_default()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn tree(&mut self) -> Result<RawTree, SgfError> {
+        let mut tree = RawTree::default();
+        self.expect('(')?;
+        self.skip_whitespace();
+
+        while self.peek() == Some(';') {
+            self.pos += 1;
+            tree.nodes.push(self.properties()?);
+            self.skip_whitespace();
+        }
+        while self.peek() == Some('(') {
+            tree.variations.push(self.tree()?);
+            self.skip_whitespace();
+        }
+
+        self.expect(')')?;
+        Ok(tree)
+    }
+
+    fn properties(&mut self) -> Result<Properties, SgfError> {
+        let mut properties = Vec::new();
+        self.skip_whitespace();
+
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            let mut ident = String::new();
+            while let Some(c) = self.peek().filter(|c| c.is_ascii_alphabetic()) {
+                ident.push(c);
+                self.pos += 1;
+            }
+            self.skip_whitespace();
+
+            let mut values = Vec::new();
+            while self.peek() == Some('[') {
+                values.push(self.value()?);
+                self.skip_whitespace();
+            }
+            if values.is_empty() {
+                let offset = self.offset();
+                return Err(SgfError::UnexpectedChar {
+                    found: self.next()?,
+                    offset,
+                });
+            }
+            properties.push((ident, values));
+        }
+
+        Ok(properties)
+    }
+
+    fn value(&mut self) -> Result<String, SgfError> {
+        self.expect('[')?;
+        let mut value = String::new();
+        loop {
+            match self.next()? {
+                ']' => return Ok(value),
+                '\\' => {
+                    let escaped = self.next()?;
+                    if escaped != '\n' {
+                        value.push(escaped);
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+    }
+}
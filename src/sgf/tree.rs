@@ -0,0 +1,435 @@
+use crate::core::game::{Game, Move};
+use crate::core::{Board, Position, Stone};
+use crate::sgf::SgfError;
+use crate::sgf::parser::{self, Properties, RawTree};
+
+#[derive(Debug, Clone, Default)]
+pub struct TreeNode {
+    pub mv: Option<Move>,
+    pub add_black: Vec<Position>,
+    pub add_white: Vec<Position>,
+    pub comment: Option<String>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// Board state after replaying the path from the root to a node.
+#[derive(Debug, Clone)]
+pub struct NodeState {
+    pub board: Board,
+    pub captures: (usize, usize),
+    pub captured: Vec<Position>,
+    pub move_numbers: Vec<Option<usize>>,
+    pub to_play: Stone,
+}
+
+impl NodeState {
+    pub fn new(size: usize) -> Self {
+        NodeState {
+            board: Board::new(size),
+            captures: (0, 0),
+            captured: Vec::new(),
+            move_numbers: vec![None; size * size],
+            to_play: Stone::Black,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameTree {
+    pub size: usize,
+    pub komi: f32,
+    pub black_player: Option<String>,
+    pub white_player: Option<String>,
+    pub result: Option<String>,
+    nodes: Vec<TreeNode>,
+}
+
+impl GameTree {
+    pub fn new(size: usize) -> Self {
+        GameTree {
+            size,
+            komi: 0.0,
+            black_player: None,
+            white_player: None,
+            result: None,
+            nodes: vec![TreeNode::default()],
+        }
+    }
+
+    pub fn from_game(game: &Game) -> Self {
+        let mut tree = GameTree::new(game.board_size());
+        tree.komi = game.komi();
+        tree.nodes[0].add_black = game.handicap().to_vec();
+
+        let mut current = tree.root();
+        for &mv in game.moves() {
+            current = tree.add_child(current, mv);
+        }
+        tree
+    }
+
+    pub fn parse(input: &str) -> Result<Self, SgfError> {
+        let raw = parser::parse_collection(input)?
+            .into_iter()
+            .next()
+            .ok_or(SgfError::UnexpectedEnd)?;
+        let root_props = raw.nodes.first().ok_or(SgfError::UnexpectedEnd)?;
+
+        let size = match property(root_props, "SZ") {
+            Some(value) => value
+                .split(':')
+                .next()
+                .and_then(|s| s.trim().parse().ok())
+                .filter(|&s| (1..=25).contains(&s))
+                .ok_or_else(|| SgfError::InvalidSize {
+                    value: value.to_string(),
+                })?,
+            None => 19,
+        };
+
+        let mut tree = GameTree::new(size);
+        tree.komi = property(root_props, "KM")
+            .and_then(|k| k.trim().parse().ok())
+            .unwrap_or(0.0);
+        tree.black_player = property(root_props, "PB").map(str::to_string);
+        tree.white_player = property(root_props, "PW").map(str::to_string);
+        tree.result = property(root_props, "RE").map(str::to_string);
+
+        tree.nodes[0] = tree.node_from_properties(root_props, None)?;
+        tree.attach(&raw, 1, 0)?;
+        Ok(tree)
+    }
+
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, SgfError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// `Some(winner)` for results like `B+R` or `W+3.5`.
+    pub fn winner(&self) -> Option<Stone> {
+        match self.result.as_deref()?.trim().as_bytes() {
+            [b'B' | b'b', b'+', ..] => Some(Stone::Black),
+            [b'W' | b'w', b'+', ..] => Some(Stone::White),
+            _ => None,
+        }
+    }
+
+    pub fn root(&self) -> usize {
+        0
+    }
+
+    pub fn node(&self, id: usize) -> &TreeNode {
+        &self.nodes[id]
+    }
+
+    pub fn node_mut(&mut self, id: usize) -> &mut TreeNode {
+        &mut self.nodes[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 1 && self.nodes[0].children.is_empty()
+    }
+
+    /// Adds `mv` as a child of `parent`, reusing an existing child that holds
+    /// the same move.
+    pub fn add_child(&mut self, parent: usize, mv: Move) -> usize {
+        if let Some(&existing) = self.nodes[parent]
+            .children
+            .iter()
+            .find(|&&child| self.nodes[child].mv == Some(mv))
+        {
+            return existing;
+        }
+
+        let id = self.nodes.len();
+        self.nodes.push(TreeNode {
+            mv: Some(mv),
+            parent: Some(parent),
+            ..Default::default()
+        });
+        self.nodes[parent].children.push(id);
+        id
+    }
+
+    pub fn path_to(&self, id: usize) -> Vec<usize> {
+        let mut path = vec![id];
+        let mut current = id;
+        while let Some(parent) = self.nodes[current].parent {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Follows the first child from `id` down to a leaf.
+    pub fn line_from(&self, id: usize) -> Vec<usize> {
+        let mut line = vec![id];
+        let mut current = id;
+        while let Some(&child) = self.nodes[current].children.first() {
+            line.push(child);
+            current = child;
+        }
+        line
+    }
+
+    pub fn main_line(&self) -> Vec<usize> {
+        self.line_from(self.root())
+    }
+
+    pub fn siblings(&self, id: usize) -> Vec<usize> {
+        match self.nodes[id].parent {
+            Some(parent) => self.nodes[parent].children.clone(),
+            None => vec![id],
+        }
+    }
+
+    pub fn state_at(&self, id: usize) -> Result<NodeState, SgfError> {
+        let mut state = NodeState::new(self.size);
+        let mut move_number = 0;
+
+        for node_id in self.path_to(id) {
+            let node = &self.nodes[node_id];
+            for (stones, color) in [
+                (&node.add_black, Stone::Black),
+                (&node.add_white, Stone::White),
+            ] {
+                for &pos in stones {
+                    state
+                        .board
+                        .place_stone(pos, color)
+                        .map_err(SgfError::IllegalSetup)?;
+                }
+            }
+
+            state.captured.clear();
+            match node.mv {
+                Some(Move::Play(stone, pos)) => {
+                    let outcome = state.board.play(pos, stone)?;
+                    move_number += 1;
+                    for &captured in &outcome.captured {
+                        state.move_numbers[state.board.pos_to_index(captured)] = None;
+                    }
+                    state.move_numbers[state.board.pos_to_index(pos)] = Some(move_number);
+                    match stone {
+                        Stone::Black => state.captures.0 += outcome.captured.len(),
+                        Stone::White => state.captures.1 += outcome.captured.len(),
+                    }
+                    state.captured = outcome.captured;
+                    state.to_play = stone.opposite();
+                }
+                Some(Move::Pass(stone)) => {
+                    move_number += 1;
+                    state.board.pass();
+                    state.to_play = stone.opposite();
+                }
+                None if !node.add_black.is_empty() && node.add_white.is_empty() => {
+                    state.to_play = Stone::White;
+                }
+                None => {}
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Replays the main line, calling `visit` with the position before each
+    /// move. Setup stones are applied first; illegal moves abort the replay.
+    pub fn replay_main_line(&self, mut visit: impl FnMut(&Board, Move)) -> Result<(), SgfError> {
+        let mut board = self.state_at(self.root())?.board;
+        for id in self.main_line().into_iter().skip(1) {
+            let Some(mv) = self.nodes[id].mv else {
+                continue;
+            };
+            visit(&board, mv);
+            match mv {
+                Move::Play(stone, pos) => {
+                    board.play(pos, stone)?;
+                }
+                Move::Pass(_) => board.pass(),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_sgf(&self) -> String {
+        let mut out = format!("(;GM[1]FF[4]SZ[{}]KM[{}]", self.size, self.komi);
+        for (ident, value) in [
+            ("PB", &self.black_player),
+            ("PW", &self.white_player),
+            ("RE", &self.result),
+        ] {
+            if let Some(value) = value {
+                out.push_str(&format!("{ident}[{}]", escape(value)));
+            }
+        }
+        self.write_node_properties(self.root(), &mut out);
+        self.write_children(self.root(), &mut out);
+        out.push(')');
+        out
+    }
+
+    fn write_children(&self, id: usize, out: &mut String) {
+        let children = &self.nodes[id].children;
+        if children.len() == 1 {
+            out.push(';');
+            self.write_node_properties(children[0], out);
+            self.write_children(children[0], out);
+        } else {
+            for &child in children {
+                out.push_str("(;");
+                self.write_node_properties(child, out);
+                self.write_children(child, out);
+                out.push(')');
+            }
+        }
+    }
+
+    fn write_node_properties(&self, id: usize, out: &mut String) {
+        let node = &self.nodes[id];
+        match node.mv {
+            Some(Move::Play(stone, pos)) => {
+                out.push_str(&format!("{}[{}]", color_ident(stone), encode(pos)))
+            }
+            Some(Move::Pass(stone)) => out.push_str(&format!("{}[]", color_ident(stone))),
+            None => {}
+        }
+        for (ident, stones) in [("AB", &node.add_black), ("AW", &node.add_white)] {
+            if !stones.is_empty() {
+                out.push_str(ident);
+                for &pos in stones {
+                    out.push_str(&format!("[{}]", encode(pos)));
+                }
+            }
+        }
+        if let Some(comment) = &node.comment {
+            out.push_str(&format!("C[{}]", escape(comment)));
+        }
+    }
+
+    fn attach(&mut self, raw: &RawTree, skip: usize, parent: usize) -> Result<(), SgfError> {
+        let mut current = parent;
+        for props in raw.nodes.iter().skip(skip) {
+            let node = self.node_from_properties(props, Some(current))?;
+            let id = self.nodes.len();
+            self.nodes.push(node);
+            self.nodes[current].children.push(id);
+            current = id;
+        }
+        for variation in &raw.variations {
+            self.attach(variation, 0, current)?;
+        }
+        Ok(())
+    }
+
+    fn node_from_properties(
+        &self,
+        props: &Properties,
+        parent: Option<usize>,
+    ) -> Result<TreeNode, SgfError> {
+        let mut node = TreeNode {
+            parent,
+            ..Default::default()
+        };
+
+        for (ident, values) in props {
+            match ident.as_str() {
+                "B" | "W" => {
+                    let stone = if ident == "B" {
+                        Stone::Black
+                    } else {
+                        Stone::White
+                    };
+                    node.mv = Some(match self.decode(&values[0])? {
+                        Some(pos) => Move::Play(stone, pos),
+                        None => Move::Pass(stone),
+                    });
+                }
+                "AB" | "AW" => {
+                    let target = if ident == "AB" {
+                        &mut node.add_black
+                    } else {
+                        &mut node.add_white
+                    };
+                    for value in values {
+                        target.extend(self.decode_list(value)?);
+                    }
+                }
+                "C" => node.comment = Some(values[0].clone()),
+                _ => {}
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn decode(&self, value: &str) -> Result<Option<Position>, SgfError> {
+        let bytes = value.as_bytes();
+        if bytes.is_empty() || (value == "tt" && self.size <= 19) {
+            return Ok(None);
+        }
+
+        let invalid = || SgfError::InvalidCoordinate {
+            value: value.to_string(),
+        };
+        if bytes.len() != 2 {
+            return Err(invalid());
+        }
+        let x = bytes[0].checked_sub(b'a').ok_or_else(invalid)? as usize;
+        let y = bytes[1].checked_sub(b'a').ok_or_else(invalid)? as usize;
+        if x >= self.size || y >= self.size {
+            return Err(invalid());
+        }
+        Ok(Some(Position { x, y }))
+    }
+
+    fn decode_list(&self, value: &str) -> Result<Vec<Position>, SgfError> {
+        let Some((from, to)) = value.split_once(':') else {
+            return Ok(self.decode(value)?.into_iter().collect());
+        };
+
+        let invalid = || SgfError::InvalidCoordinate {
+            value: value.to_string(),
+        };
+        let from = self.decode(from)?.ok_or_else(invalid)?;
+        let to = self.decode(to)?.ok_or_else(invalid)?;
+
+        let mut points = Vec::new();
+        for y in from.y.min(to.y)..=from.y.max(to.y) {
+            for x in from.x.min(to.x)..=from.x.max(to.x) {
+                points.push(Position { x, y });
+            }
+        }
+        Ok(points)
+    }
+}
+
+fn property<'a>(props: &'a Properties, ident: &str) -> Option<&'a str> {
+    props
+        .iter()
+        .find(|(name, _)| name == ident)
+        .and_then(|(_, values)| values.first())
+        .map(String::as_str)
+}
+
+fn color_ident(stone: Stone) -> &'static str {
+    match stone {
+        Stone::Black => "B",
+        Stone::White => "W",
+    }
+}
+
+fn encode(pos: Position) -> String {
+    [pos.x, pos.y]
+        .iter()
+        .map(|&c| (b'a' + c as u8) as char)
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(']', "\\]")
+}
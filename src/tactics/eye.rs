@@ -0,0 +1,247 @@
+use crate::core::{Board, Position, Stone, Symmetry};
+
+const MAX_EYE_SPACE: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EyeKind {
+    Real,
+    False,
+    Potential,
+    NotEye,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EyeShape {
+    Single,
+    Two,
+    StraightThree,
+    BentThree,
+    SquareFour,
+    PyramidFour,
+    StraightFour,
+    BentFour,
+    ZigzagFour,
+    BulkyFive,
+    CrossedFive,
+    RabbitySix,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeStatus {
+    Alive,
+    Dead,
+    Unsettled,
+}
+
+#[derive(Debug, Clone)]
+pub struct EyeSpace {
+    pub points: Vec<Position>,
+    pub shape: Option<EyeShape>,
+    pub vital_point: Option<Position>,
+}
+
+struct Pattern {
+    shape: EyeShape,
+    points: &'static [(i32, i32)],
+    vital: Option<(i32, i32)>,
+}
+
+const PATTERNS: &[Pattern] = &[
+    Pattern {
+        shape: EyeShape::Single,
+        points: &[(0, 0)],
+        vital: None,
+    },
+    Pattern {
+        shape: EyeShape::Two,
+        points: &[(0, 0), (1, 0)],
+        vital: None,
+    },
+    Pattern {
+        shape: EyeShape::StraightThree,
+        points: &[(0, 0), (1, 0), (2, 0)],
+        vital: Some((1, 0)),
+    },
+    Pattern {
+        shape: EyeShape::BentThree,
+        points: &[(0, 0), (1, 0), (0, 1)],
+        vital: Some((0, 0)),
+    },
+    Pattern {
+        shape: EyeShape::SquareFour,
+        points: &[(0, 0), (1, 0), (0, 1), (1, 1)],
+        vital: None,
+    },
+    Pattern {
+        shape: EyeShape::PyramidFour,
+        points: &[(0, 0), (1, 0), (2, 0), (1, 1)],
+        vital: Some((1, 0)),
+    },
+    Pattern {
+        shape: EyeShape::StraightFour,
+        points: &[(0, 0), (1, 0), (2, 0), (3, 0)],
+        vital: None,
+    },
+    Pattern {
+        shape: EyeShape::BentFour,
+        points: &[(0, 0), (1, 0), (2, 0), (0, 1)],
+        vital: None,
+    },
+    Pattern {
+        shape: EyeShape::ZigzagFour,
+        points: &[(0, 0), (1, 0), (1, 1), (2, 1)],
+        vital: None,
+    },
+    Pattern {
+        shape: EyeShape::BulkyFive,
+        points: &[(0, 0), (1, 0), (0, 1), (1, 1), (2, 1)],
+        vital: Some((1, 1)),
+    },
+    Pattern {
+        shape: EyeShape::CrossedFive,
+        points: &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
+        vital: Some((1, 1)),
+    },
+    Pattern {
+        shape: EyeShape::RabbitySix,
+        points: &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2), (2, 2)],
+        vital: Some((1, 1)),
+    },
+];
+
+impl EyeShape {
+    /// Status of a fully enclosed eye space of this shape, ignoring outside
+    /// liberties and defects in the surrounding wall.
+    pub fn status(&self) -> ShapeStatus {
+        match self {
+            EyeShape::Single | EyeShape::Two | EyeShape::SquareFour => ShapeStatus::Dead,
+            EyeShape::StraightFour | EyeShape::BentFour | EyeShape::ZigzagFour => {
+                ShapeStatus::Alive
+            }
+            _ => ShapeStatus::Unsettled,
+        }
+    }
+}
+
+/// Classifies an empty point as an eye of `color`. A point fully surrounded by
+/// `color` is false when the opponent holds two diagonals, or one diagonal on
+/// the edge or in the corner.
+pub fn classify(board: &Board, pos: Position, color: Stone) -> EyeKind {
+    if board.get_stone(pos).ok() != Some(None) {
+        return EyeKind::NotEye;
+    }
+
+    let mut own = 0;
+    let neighbors = board.get_neighbors(pos);
+    for &neighbor in &neighbors {
+        match board.get_stone(neighbor).ok().flatten() {
+            Some(stone) if stone == color => own += 1,
+            Some(_) => return EyeKind::NotEye,
+            None => {}
+        }
+    }
+    if own == 0 {
+        return EyeKind::NotEye;
+    }
+
+    let diagonals = diagonals(board, pos);
+    let opponent_diagonals = diagonals
+        .iter()
+        .filter(|&&d| board.get_stone(d).ok().flatten() == Some(color.opposite()))
+        .count();
+    let allowed = if diagonals.len() < 4 { 0 } else { 1 };
+
+    if opponent_diagonals > allowed {
+        EyeKind::False
+    } else if own == neighbors.len() {
+        EyeKind::Real
+    } else {
+        EyeKind::Potential
+    }
+}
+
+/// Small regions of empty points and opponent stones enclosed by `color`.
+pub fn eye_spaces(board: &Board, color: Stone) -> Vec<EyeSpace> {
+    let size = board.size();
+    let mut visited = vec![false; size * size];
+    let mut spaces = Vec::new();
+
+    for y in 0..size {
+        for x in 0..size {
+            let start = Position { x, y };
+            let idx = board.pos_to_index(start);
+            if visited[idx] || board.get_stone(start).ok().flatten() == Some(color) {
+                continue;
+            }
+
+            let mut points = Vec::new();
+            let mut stack = vec![start];
+            visited[idx] = true;
+            while let Some(pos) = stack.pop() {
+                points.push(pos);
+                for neighbor in board.get_neighbors(pos) {
+                    let n_idx = board.pos_to_index(neighbor);
+                    if !visited[n_idx] && board.get_stone(neighbor).ok().flatten() != Some(color) {
+                        visited[n_idx] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            let has_empty = points
+                .iter()
+                .any(|&p| board.get_stone(p).ok().flatten().is_none());
+            if points.len() > MAX_EYE_SPACE || !has_empty {
+                continue;
+            }
+
+            let (shape, vital_point) = match_shape(&points);
+            spaces.push(EyeSpace {
+                points,
+                shape,
+                vital_point,
+            });
+        }
+    }
+
+    spaces
+}
+
+fn diagonals(board: &Board, pos: Position) -> Vec<Position> {
+    let size = board.size() as i32;
+    [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+        .iter()
+        .map(|(dx, dy)| (pos.x as i32 + dx, pos.y as i32 + dy))
+        .filter(|&(x, y)| x >= 0 && y >= 0 && x < size && y < size)
+        .map(|(x, y)| Position {
+            x: x as usize,
+            y: y as usize,
+        })
+        .collect()
+}
+
+fn match_shape(points: &[Position]) -> (Option<EyeShape>, Option<Position>) {
+    for pattern in PATTERNS.iter().filter(|p| p.points.len() == points.len()) {
+        for symmetry in Symmetry::ALL {
+            let transformed: Vec<(i32, i32)> = points
+                .iter()
+                .map(|p| symmetry.apply_offset((p.x as i32, p.y as i32)))
+                .collect();
+            let min_x = transformed.iter().map(|p| p.0).min().unwrap_or(0);
+            let min_y = transformed.iter().map(|p| p.1).min().unwrap_or(0);
+            let normalized: Vec<(i32, i32)> = transformed
+                .iter()
+                .map(|&(x, y)| (x - min_x, y - min_y))
+                .collect();
+
+            if normalized.iter().all(|p| pattern.points.contains(p)) {
+                let vital = pattern
+                    .vital
+                    .and_then(|v| normalized.iter().position(|&p| p == v).map(|i| points[i]));
+                return (Some(pattern.shape), vital);
+            }
+        }
+    }
+
+    (None, None)
+}
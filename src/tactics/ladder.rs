@@ -0,0 +1,98 @@
+use crate::core::{Board, Position, Stone};
+
+/// Ladders longer than this are taken to work; none fits on a 25x25 board.
+const MAX_DEPTH: usize = 200;
+
+/// Whether the group at `target` dies in a ladder with `to_play` to move:
+/// the attacker keeps it in atari until it is captured, and the defender
+/// can neither gain liberties nor capture its way out.
+pub fn is_captured(board: &Board, target: Position, to_play: Stone) -> bool {
+    read(board, target, to_play, 0)
+}
+
+/// Whether playing `pos` ataris an opponent group that then dies in a
+/// ladder.
+pub fn is_ladder_capture(board: &Board, pos: Position, player: Stone) -> bool {
+    let mut after = board.clone();
+    if after.play(pos, player).is_err() {
+        return false;
+    }
+    after.get_neighbors(pos).into_iter().any(|neighbor| {
+        after.get_stone(neighbor).ok() == Some(Some(player.opposite()))
+            && after.count_liberties(&after.get_group(neighbor)) == 1
+            && read(&after, neighbor, player.opposite(), 0)
+    })
+}
+
+/// Whether `player` saves a group in atari by extending to `pos`, its last
+/// liberty, when the group would otherwise be caught in a ladder.
+pub fn is_ladder_escape(board: &Board, pos: Position, player: Stone) -> bool {
+    let in_atari: Vec<Position> = board
+        .get_neighbors(pos)
+        .into_iter()
+        .filter(|&neighbor| {
+            board.get_stone(neighbor).ok() == Some(Some(player))
+                && board.liberties(&board.get_group(neighbor)) == [pos]
+        })
+        .collect();
+    if in_atari.is_empty() {
+        return false;
+    }
+    let mut after = board.clone();
+    after.play(pos, player).is_ok() && !read(&after, pos, player.opposite(), 0)
+}
+
+fn read(board: &Board, target: Position, to_play: Stone, depth: usize) -> bool {
+    let Ok(Some(defender)) = board.get_stone(target) else {
+        return true;
+    };
+    if depth > MAX_DEPTH {
+        return true;
+    }
+    let group = board.get_group(target);
+    let liberties = board.liberties(&group);
+
+    if to_play != defender {
+        return match liberties.len() {
+            0 | 1 => true,
+            2 => liberties.iter().any(|&pos| {
+                let mut after = board.clone();
+                after.play(pos, to_play).is_ok() && read(&after, target, defender, depth + 1)
+            }),
+            _ => false,
+        };
+    }
+
+    match liberties.len() {
+        0 => true,
+        1 => {
+            let mut escapes = capturing_moves(board, &group, defender);
+            escapes.push(liberties[0]);
+            escapes.into_iter().all(|pos| {
+                let mut after = board.clone();
+                after.play(pos, defender).is_err()
+                    || read(&after, target, defender.opposite(), depth + 1)
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Last liberties of attacking groups in atari that touch `group`.
+fn capturing_moves(board: &Board, group: &[Position], defender: Stone) -> Vec<Position> {
+    let mut moves = Vec::new();
+    for &stone in group {
+        for neighbor in board.get_neighbors(stone) {
+            if board.get_stone(neighbor).ok() != Some(Some(defender.opposite())) {
+                continue;
+            }
+            let liberties = board.liberties(&board.get_group(neighbor));
+            if let [liberty] = liberties[..]
+                && !moves.contains(&liberty)
+            {
+                moves.push(liberty);
+            }
+        }
+    }
+    moves
+}
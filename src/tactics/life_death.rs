@@ -0,0 +1,404 @@
+use crate::core::{Board, Position, Stone};
+use std::collections::HashMap;
+
+const INFINITY: u32 = u32::MAX / 2;
+/// Threshold above every proof number, for searching until solved. Depth
+/// cuts make numbers reach `INFINITY` without solving the node.
+const UNBOUNDED: u32 = INFINITY + 1;
+const WHITE_TO_MOVE_KEY: u64 = 0x9e37_79b9_7f4a_7c15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Alive,
+    Dead,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub status: Status,
+    pub key_move: Option<Position>,
+    pub main_line: Vec<Option<Position>>,
+    pub nodes: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProofNumbers {
+    proof: u32,
+    disproof: u32,
+}
+
+impl ProofNumbers {
+    const UNKNOWN: Self = Self {
+        proof: 1,
+        disproof: 1,
+    };
+    const PROVEN: Self = Self {
+        proof: 0,
+        disproof: INFINITY,
+    };
+    const DISPROVEN: Self = Self {
+        proof: INFINITY,
+        disproof: 0,
+    };
+    /// A node past the depth limit: neither side can win it, so the search
+    /// turns to other moves instead of coming back to it.
+    const DEPTH_CUT: Self = Self {
+        proof: INFINITY,
+        disproof: INFINITY,
+    };
+
+    fn is_solved(&self) -> bool {
+        self.proof == 0 || self.disproof == 0
+    }
+}
+
+struct Problem<'a> {
+    target: Position,
+    defender: Stone,
+    region: &'a [Position],
+}
+
+/// Depth-first proof-number search. The attacker is the prover: a node is
+/// proven when the target group gets captured and disproven when the group
+/// becomes pass-alive or the attacker runs out of moves inside the region.
+/// Only the defender may pass.
+pub struct Solver {
+    node_limit: usize,
+    max_depth: usize,
+    table: HashMap<u64, ProofNumbers>,
+    nodes: usize,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new(100_000, 64)
+    }
+}
+
+impl Solver {
+    pub fn new(node_limit: usize, max_depth: usize) -> Self {
+        Solver {
+            node_limit,
+            max_depth,
+            table: HashMap::new(),
+            nodes: 0,
+        }
+    }
+
+    pub fn solve(
+        &mut self,
+        board: &Board,
+        target: Position,
+        region: &[Position],
+        to_play: Stone,
+    ) -> Solution {
+        self.table.clear();
+        self.nodes = 0;
+
+        // Without a group on `target` there is nothing to read out.
+        let Ok(Some(defender)) = board.get_stone(target) else {
+            return Solution {
+                status: Status::Unknown,
+                key_move: None,
+                main_line: Vec::new(),
+                nodes: 0,
+            };
+        };
+        let problem = Problem {
+            target,
+            defender,
+            region,
+        };
+
+        let threshold = ProofNumbers {
+            proof: UNBOUNDED,
+            disproof: UNBOUNDED,
+        };
+        let (root, _) = self.mid(board, to_play, 0, threshold, &problem);
+
+        let status = if root.proof == 0 {
+            Status::Dead
+        } else if root.disproof == 0 {
+            Status::Alive
+        } else {
+            Status::Unknown
+        };
+
+        let main_line = match status {
+            Status::Unknown => Vec::new(),
+            _ => self.main_line(board, to_play, status == Status::Dead, &problem),
+        };
+        let winner_to_play = match status {
+            Status::Dead => to_play != defender,
+            Status::Alive => to_play == defender,
+            Status::Unknown => false,
+        };
+        let key_move = if winner_to_play {
+            main_line.first().copied().flatten()
+        } else {
+            None
+        };
+
+        Solution {
+            status,
+            key_move,
+            main_line,
+            nodes: self.nodes,
+        }
+    }
+
+    /// Searches the node until its numbers reach `threshold`. Also returns
+    /// whether the numbers rest on a depth cut somewhere below; such
+    /// unsolved numbers are not cached, so that a shallower visit can still
+    /// solve the position.
+    fn mid(
+        &mut self,
+        board: &Board,
+        to_move: Stone,
+        depth: usize,
+        threshold: ProofNumbers,
+        problem: &Problem,
+    ) -> (ProofNumbers, bool) {
+        self.nodes += 1;
+        let key = position_key(board, to_move);
+
+        if board.get_stone(problem.target).ok().flatten() != Some(problem.defender) {
+            self.table.insert(key, ProofNumbers::PROVEN);
+            return (ProofNumbers::PROVEN, false);
+        }
+        if board.is_pass_alive(problem.target) {
+            self.table.insert(key, ProofNumbers::DISPROVEN);
+            return (ProofNumbers::DISPROVEN, false);
+        }
+        // Too deep to read out: neither side has won.
+        if depth >= self.max_depth {
+            return (ProofNumbers::DEPTH_CUT, true);
+        }
+
+        let attacking = to_move != problem.defender;
+        let children = self.children(board, to_move, problem);
+        if children.is_empty() {
+            self.table.insert(key, ProofNumbers::DISPROVEN);
+            return (ProofNumbers::DISPROVEN, false);
+        }
+
+        let mut numbers: Vec<ProofNumbers> = children
+            .iter()
+            .map(|(_, child)| {
+                let child_key = position_key(child, to_move.opposite());
+                self.table
+                    .get(&child_key)
+                    .copied()
+                    .unwrap_or(ProofNumbers::UNKNOWN)
+            })
+            .collect();
+        let mut cut = vec![false; children.len()];
+
+        loop {
+            let (current, best, second) = aggregate(&numbers, attacking);
+            // Once the number the node chooses by is infinite, every child
+            // is solved against it or cut, and there is nothing left to read.
+            let stuck = if attacking {
+                current.proof >= INFINITY
+            } else {
+                current.disproof >= INFINITY
+            };
+            if current.is_solved()
+                || stuck
+                || current.proof >= threshold.proof
+                || current.disproof >= threshold.disproof
+                || self.nodes >= self.node_limit
+            {
+                // Cut children can neither prove nor disprove a node, so a
+                // solved result holds at any depth.
+                let cut = !current.is_solved() && cut.contains(&true);
+                if !cut {
+                    self.table.insert(key, current);
+                }
+                return (current, cut);
+            }
+
+            let child = numbers[best];
+            let child_threshold = if attacking {
+                ProofNumbers {
+                    proof: threshold.proof.min(second.saturating_add(1)),
+                    disproof: (threshold.disproof - current.disproof)
+                        .saturating_add(child.disproof)
+                        .min(UNBOUNDED),
+                }
+            } else {
+                ProofNumbers {
+                    proof: (threshold.proof - current.proof)
+                        .saturating_add(child.proof)
+                        .min(UNBOUNDED),
+                    disproof: threshold.disproof.min(second.saturating_add(1)),
+                }
+            };
+
+            (numbers[best], cut[best]) = self.mid(
+                &children[best].1,
+                to_move.opposite(),
+                depth + 1,
+                child_threshold,
+                problem,
+            );
+        }
+    }
+
+    fn children(
+        &self,
+        board: &Board,
+        to_move: Stone,
+        problem: &Problem,
+    ) -> Vec<(Option<Position>, Board)> {
+        let mut children = Vec::new();
+
+        for &pos in problem.region {
+            if board.get_stone(pos).ok() != Some(None) {
+                continue;
+            }
+            let mut child = board.clone();
+            if child.place_stone(pos, to_move).is_ok() {
+                children.push((Some(pos), child));
+            }
+        }
+
+        if to_move == problem.defender {
+            children.push((None, board.clone()));
+        }
+
+        children
+    }
+
+    fn main_line(
+        &self,
+        board: &Board,
+        to_move: Stone,
+        proven: bool,
+        problem: &Problem,
+    ) -> Vec<Option<Position>> {
+        let mut line = Vec::new();
+        let mut board = board.clone();
+        let mut to_move = to_move;
+
+        while line.len() < self.max_depth
+            && board.get_stone(problem.target).ok().flatten() == Some(problem.defender)
+        {
+            let next = self
+                .children(&board, to_move, problem)
+                .into_iter()
+                .find(|(_, child)| {
+                    let key = position_key(child, to_move.opposite());
+                    match self.table.get(&key) {
+                        Some(numbers) if proven => numbers.proof == 0,
+                        Some(numbers) => numbers.disproof == 0,
+                        None => false,
+                    }
+                });
+
+            let Some((mv, child)) = next else {
+                break;
+            };
+            line.push(mv);
+            board = child;
+            to_move = to_move.opposite();
+        }
+
+        line
+    }
+}
+
+pub fn solve(board: &Board, target: Position, region: &[Position], to_play: Stone) -> Solution {
+    Solver::default().solve(board, target, region, to_play)
+}
+
+fn position_key(board: &Board, to_move: Stone) -> u64 {
+    let side = match to_move {
+        Stone::Black => 0,
+        Stone::White => WHITE_TO_MOVE_KEY,
+    };
+    board.hash() ^ side ^ board.ko_key()
+}
+
+/// Returns the node's proof numbers, the index of the child to expand and the
+/// second best proof (OR node) or disproof (AND node) number.
+fn aggregate(numbers: &[ProofNumbers], attacking: bool) -> (ProofNumbers, usize, u32) {
+    let select = |n: &ProofNumbers| if attacking { n.proof } else { n.disproof };
+
+    let mut best = 0;
+    let mut second = INFINITY;
+    for (i, n) in numbers.iter().enumerate().skip(1) {
+        if select(n) < select(&numbers[best]) {
+            second = select(&numbers[best]);
+            best = i;
+        } else if select(n) < second {
+            second = select(n);
+        }
+    }
+
+    let sum = numbers
+        .iter()
+        .map(|n| if attacking { n.disproof } else { n.proof })
+        .fold(0u32, |acc, n| acc.saturating_add(n))
+        .min(INFINITY);
+
+    let current = if attacking {
+        ProofNumbers {
+            proof: numbers[best].proof,
+            disproof: sum,
+        }
+    } else {
+        ProofNumbers {
+            proof: sum,
+            disproof: numbers[best].disproof,
+        }
+    };
+
+    (current, best, second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_cuts_do_not_block_shallower_visits() {
+        // Straight three: White kills by playing the middle, but with Black
+        // passing the capture only comes seven moves in.
+        let mut board = Board::new(5);
+        for x in 0..4 {
+            board
+                .place_stone(Position { x, y: 1 }, Stone::Black)
+                .unwrap();
+        }
+        board
+            .place_stone(Position { x: 3, y: 0 }, Stone::Black)
+            .unwrap();
+        for pos in [(4, 0), (4, 1), (0, 2), (1, 2), (2, 2), (3, 2), (4, 2)] {
+            let pos = Position { x: pos.0, y: pos.1 };
+            board.place_stone(pos, Stone::White).unwrap();
+        }
+        let region: Vec<Position> = (0..3).map(|x| Position { x, y: 0 }).collect();
+        let problem = Problem {
+            target: Position { x: 3, y: 0 },
+            defender: Stone::Black,
+            region: &region,
+        };
+        let threshold = ProofNumbers {
+            proof: UNBOUNDED,
+            disproof: UNBOUNDED,
+        };
+
+        let mut solver = Solver::new(100_000, 7);
+        let (deep, cut) = solver.mid(&board, Stone::White, 1, threshold, &problem);
+        assert!(!deep.is_solved() && cut, "The capture lies past the limit");
+
+        let (shallow, _) = solver.mid(&board, Stone::White, 0, threshold, &problem);
+        assert_eq!(
+            shallow,
+            ProofNumbers::PROVEN,
+            "Reached earlier, it is read out"
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod eye;
+pub mod ladder;
+pub mod life_death;
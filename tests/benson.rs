@@ -0,0 +1,60 @@
+mod common;
+
+use betago::*;
+use common::{board_from_rows, pos};
+
+#[test]
+fn two_eyes_are_pass_alive() {
+    let board = board_from_rows(&[
+        ". B . B W",
+        "B B B B W",
+        "W W W W W",
+        ". . . . .",
+        ". . . . .",
+    ]);
+
+    let result = board.pass_alive(Stone::Black);
+    assert!(result.is_alive(pos(1, 0)), "Two-eyed group is pass-alive");
+    assert!(result.is_territory(pos(0, 0)), "Eyes should be territory");
+    assert!(result.is_territory(pos(2, 0)), "Eyes should be territory");
+    assert!(board.is_pass_alive(pos(3, 1)));
+}
+
+#[test]
+fn single_eye_is_not_pass_alive() {
+    let board = board_from_rows(&[
+        ". . B W .",
+        "B B B W .",
+        "W W W W .",
+        ". . . . .",
+        ". . . . .",
+    ]);
+
+    let result = board.pass_alive(Stone::Black);
+    assert!(result.chains.is_empty(), "Group with one eye can be captured");
+    assert!(result.territory.is_empty());
+}
+
+#[test]
+fn dead_stone_inside_eye_is_territory() {
+    let board = board_from_rows(&[
+        "W B . B W",
+        ". B B B W",
+        "B B W W W",
+        "W W W . .",
+        ". . . . .",
+    ]);
+    let result = board.pass_alive(Stone::Black);
+    assert!(result.is_alive(pos(1, 0)), "Eye with enemy stone still counts");
+    assert!(result.is_territory(pos(0, 0)), "Enemy stone is inside territory");
+    assert!(result.is_territory(pos(0, 1)));
+}
+
+#[test]
+fn empty_board_has_no_pass_alive_chains() {
+    let board = Board::new(9);
+    let result = board.pass_alive(Stone::White);
+
+    assert!(result.chains.is_empty());
+    assert!(result.territory.is_empty());
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use betago::ai::GoAI;
+use betago::ai::book::{BookAI, BookError, OpeningBook};
+use betago::ai::heuristic::HeuristicAI;
+use betago::ai::tuning::MatchSettings;
+use betago::core::seed;
+use betago::sgf::GameTree;
+use betago::{Board, Stone};
+use common::pos;
+use std::sync::Arc;
+
+fn book_from(games: &[&str]) -> OpeningBook {
+    let mut book = OpeningBook::new(10);
+    for game in games {
+        book.add_game(&GameTree::parse(game).unwrap()).unwrap();
+    }
+    book
+}
+
+#[test]
+fn symmetric_openings_share_statistics() {
+    let book = book_from(&[
+        "(;GM[1]SZ[9]RE[B+R];B[cc];W[gg])",
+        "(;GM[1]SZ[9]RE[W+3.5];B[gc];W[cg])",
+    ]);
+
+    let first = book.moves(&Board::new(9), Stone::Black);
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].games, 2);
+    assert_eq!(first[0].wins, 1.0);
+    assert!([pos(2, 2), pos(6, 2), pos(2, 6), pos(6, 6)].contains(&first[0].pos));
+}
+
+#[test]
+fn book_moves_follow_the_board_orientation() {
+    let book = book_from(&["(;GM[1]SZ[9]RE[B+R];B[cc];W[gg])"]);
+
+    let mut mirrored = Board::new(9);
+    mirrored.place_stone(pos(6, 2), Stone::Black).unwrap();
+    let moves = book.moves(&mirrored, Stone::White);
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].pos, pos(2, 6));
+    assert_eq!(moves[0].wins, 0.0, "White lost this game");
+
+    assert!(book.moves(&mirrored, Stone::Black).is_empty());
+}
+
+#[test]
+fn only_the_opening_is_recorded() {
+    let mut book = OpeningBook::new(1);
+    book.add_game(&GameTree::parse("(;GM[1]SZ[9];B[cc];W[gg];B[ee])").unwrap())
+        .unwrap();
+
+    assert_eq!(book.len(), 1);
+    assert_eq!(book.moves(&Board::new(9), Stone::Black)[0].wins, 0.5);
+}
+
+#[test]
+fn book_round_trips_through_a_file() {
+    let book = book_from(&["(;GM[1]SZ[9]RE[B+R];B[cc];W[gg];B[gc])"]);
+
+    let path = std::env::temp_dir().join(format!("betago-book-{}.bin", std::process::id()));
+    book.save(&path).unwrap();
+    let loaded = OpeningBook::load(&path);
+    std::fs::write(&path, b"BGPT").unwrap();
+    let garbage = OpeningBook::load(&path);
+    std::fs::write(&path, b"BGOB\x02\0\0\0").unwrap();
+    let newer = OpeningBook::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap(), book);
+    assert!(matches!(garbage, Err(BookError::BadMagic)));
+    assert!(matches!(newer, Err(BookError::UnsupportedVersion(2))));
+}
+
+#[test]
+fn wrapped_engine_plays_from_the_book_then_takes_over() {
+    let book = Arc::new(book_from(&["(;GM[1]SZ[9]RE[B+R];B[cc];W[gg])"]));
+    let mut ai = BookAI::new(book, HeuristicAI::default(), 3);
+
+    let mut board = Board::new(9);
+    let opening = ai.select_move(&board, Stone::Black).unwrap();
+    assert!([pos(2, 2), pos(6, 2), pos(2, 6), pos(6, 6)].contains(&opening));
+
+    board.place_stone(pos(4, 4), Stone::Black).unwrap();
+    assert_eq!(
+        ai.select_move(&board, Stone::White),
+        HeuristicAI::default().select_move(&board, Stone::White)
+    );
+}
+
+#[test]
+fn self_play_fills_the_book_reproducibly() {
+    let settings = MatchSettings {
+        board_size: 7,
+        max_moves: 60,
+        ..MatchSettings::default()
+    };
+    let build = || {
+        let mut book = OpeningBook::new(6);
+        book.add_self_play(
+            &mut HeuristicAI::default(),
+            &mut HeuristicAI::default(),
+            4,
+            &settings,
+            &mut seed::rng(11),
+        )
+        .unwrap();
+        book
+    };
+
+    let book = build();
+    assert!(book.len() > 6);
+    assert_eq!(book, build());
+}
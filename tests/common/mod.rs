@@ -0,0 +1,25 @@
+// Each test crate compiles its own copy and uses only some of the helpers.
+#![allow(dead_code)]
+
+use betago::*;
+
+pub fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+pub fn board_from_rows(rows: &[&str]) -> Board {
+    let mut board = Board::new(rows.len());
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.split_whitespace().enumerate() {
+            let stone = match c {
+                "B" => Stone::Black,
+                "W" => Stone::White,
+                _ => continue,
+            };
+            board.place_stone(pos(x, y), stone).unwrap();
+        }
+    }
+
+    board
+}
@@ -0,0 +1,59 @@
+use betago::ai::playout::PlayoutPolicy;
+use betago::config::{ConfigError, ConfigFile};
+use betago::core::Rules;
+use betago::gui::settings::{EngineKind, Settings};
+
+#[test]
+fn parses_keys_comments_and_blank_lines() {
+    let config = ConfigFile::parse("# comment\n\nkomi = 7.5\n  black.engine=human  \n").unwrap();
+
+    assert_eq!(config.get::<f32>("komi").unwrap(), Some(7.5));
+    assert_eq!(config.raw("black.engine"), Some("human"));
+    assert_eq!(config.get::<usize>("missing").unwrap(), None);
+}
+
+#[test]
+fn reports_syntax_errors_with_line_numbers() {
+    assert!(matches!(
+        ConfigFile::parse("komi = 6.5\nboard_size 19\n"),
+        Err(ConfigError::Syntax { line: 2 })
+    ));
+}
+
+#[test]
+fn rejects_malformed_values() {
+    let config = ConfigFile::parse("board_size = nineteen").unwrap();
+    assert!(matches!(
+        config.get::<usize>("board_size"),
+        Err(ConfigError::InvalidValue { .. })
+    ));
+}
+
+#[test]
+fn settings_round_trip() {
+    let mut settings = Settings {
+        board_size: 13,
+        komi: 0.5,
+        rules: Rules::Chinese,
+        handicap: 4,
+        ..Settings::default()
+    };
+    settings.black.kind = EngineKind::Human;
+    settings.white.playout = PlayoutPolicy::heavy();
+    settings.white.kind = EngineKind::Network;
+    settings.white.weights = Some("nets/9x9.bin".into());
+    settings.black.patterns = Some("patterns.bin".into());
+
+    let text = settings.to_config().to_string();
+    let loaded = Settings::from_config(&ConfigFile::parse(&text).unwrap()).unwrap();
+
+    assert_eq!(loaded, settings);
+}
+
+#[test]
+fn settings_reject_unknown_engines_and_sizes() {
+    for text in ["white.engine = gnugo", "board_size = 99", "rules = korean"] {
+        let config = ConfigFile::parse(text).unwrap();
+        assert!(Settings::from_config(&config).is_err(), "{text}");
+    }
+}
@@ -0,0 +1,87 @@
+use betago::ai::heuristic::HeuristicAI;
+use betago::ai::ownership::{MonteCarloEstimator, OwnershipEstimator};
+use betago::ai::playout::PlayoutPolicy;
+use betago::ai::random::RandomAI;
+use betago::ai::tuning::{MatchSettings, play_game};
+use betago::core::game::Move;
+use betago::core::seed;
+use betago::{Board, Position, Stone};
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn self_play(game_seed: u64) -> Vec<Move> {
+    let mut black = RandomAI::new(PlayoutPolicy::uniform(0.05), seed::derive(game_seed, 1));
+    let mut white = RandomAI::new(PlayoutPolicy::uniform(0.05), seed::derive(game_seed, 2));
+    let settings = MatchSettings {
+        board_size: 9,
+        max_moves: 120,
+        ..MatchSettings::default()
+    };
+
+    play_game(
+        &mut black,
+        &mut white,
+        &settings,
+        &mut seed::rng(seed::derive(game_seed, 0)),
+    )
+    .moves()
+    .to_vec()
+}
+
+#[test]
+fn zobrist_hashes_agree_across_boards() {
+    let mut first = Board::new(9);
+    let mut second = Board::new(9);
+    for board in [&mut first, &mut second] {
+        board.place_stone(pos(2, 2), Stone::Black).unwrap();
+        board.place_stone(pos(6, 6), Stone::White).unwrap();
+    }
+
+    assert_ne!(first.hash(), 0);
+    assert_eq!(first.hash(), second.hash());
+}
+
+#[test]
+fn self_play_replays_from_the_same_seed() {
+    assert_eq!(self_play(2024), self_play(2024));
+    assert_ne!(self_play(2024), self_play(2025));
+}
+
+#[test]
+fn heuristic_games_replay_with_seeded_openings() {
+    let settings = MatchSettings {
+        board_size: 7,
+        max_moves: 80,
+        ..MatchSettings::default()
+    };
+    let play = || {
+        play_game(
+            &mut HeuristicAI::default(),
+            &mut HeuristicAI::default(),
+            &settings,
+            &mut seed::rng(5),
+        )
+        .moves()
+        .to_vec()
+    };
+
+    assert_eq!(play(), play());
+}
+
+#[test]
+fn monte_carlo_ownership_is_reproducible() {
+    let mut board = Board::new(7);
+    board.place_stone(pos(3, 3), Stone::Black).unwrap();
+    board.place_stone(pos(4, 3), Stone::White).unwrap();
+
+    let estimator = MonteCarloEstimator {
+        playouts: 8,
+        seed: 11,
+    };
+    assert_eq!(
+        estimator.estimate(&board, Stone::Black),
+        estimator.estimate(&board, Stone::Black)
+    );
+}
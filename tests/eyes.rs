@@ -0,0 +1,113 @@
+mod common;
+
+use betago::Stone;
+use betago::tactics::eye::{EyeKind, EyeShape, ShapeStatus, classify, eye_spaces};
+use common::{board_from_rows, pos};
+
+#[test]
+fn real_eye_in_the_center() {
+    let board = board_from_rows(&[
+        ". . . . .",
+        ". . B W .",
+        ". B . B .",
+        ". . B . .",
+        ". . . . .",
+    ]);
+
+    assert_eq!(classify(&board, pos(2, 2), Stone::Black), EyeKind::Real);
+}
+
+#[test]
+fn two_enemy_diagonals_make_a_false_eye() {
+    let board = board_from_rows(&[
+        ". . . . .",
+        ". W B W .",
+        ". B . B .",
+        ". W B . .",
+        ". . . . .",
+    ]);
+
+    assert_eq!(classify(&board, pos(2, 2), Stone::Black), EyeKind::False);
+}
+
+#[test]
+fn one_enemy_diagonal_is_enough_on_the_edge() {
+    let board = board_from_rows(&[
+        ". B . B .",
+        ". W B . .",
+        ". . . . .",
+        ". . . . .",
+        ". . . . .",
+    ]);
+
+    assert_eq!(classify(&board, pos(2, 0), Stone::Black), EyeKind::False);
+    assert_eq!(classify(&board, pos(4, 0), Stone::Black), EyeKind::Potential);
+}
+
+#[test]
+fn occupied_or_contested_points_are_not_eyes() {
+    let board = board_from_rows(&[
+        ". B W . .",
+        ". . . . .",
+        ". . . . .",
+        ". . . . .",
+        ". . . . .",
+    ]);
+
+    assert_eq!(classify(&board, pos(1, 0), Stone::Black), EyeKind::NotEye);
+    assert_eq!(classify(&board, pos(3, 3), Stone::Black), EyeKind::NotEye);
+    assert_eq!(classify(&board, pos(2, 1), Stone::Black), EyeKind::NotEye);
+}
+
+#[test]
+fn straight_three_vital_point() {
+    let board = board_from_rows(&[
+        ". . . B .",
+        "B B B B .",
+        ". . . . .",
+        ". . . . .",
+        ". . . . .",
+    ]);
+
+    let spaces = eye_spaces(&board, Stone::Black);
+    assert_eq!(spaces.len(), 1, "Outside region is too big to be an eye");
+    assert_eq!(spaces[0].shape, Some(EyeShape::StraightThree));
+    assert_eq!(spaces[0].vital_point, Some(pos(1, 0)));
+}
+
+#[test]
+fn bulky_five_and_square_four() {
+    let board = board_from_rows(&[
+        ". . B . . . .",
+        ". . . B . . .",
+        "B B B B . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+    ]);
+
+    let spaces = eye_spaces(&board, Stone::Black);
+    let bulky = spaces
+        .iter()
+        .find(|s| s.shape == Some(EyeShape::BulkyFive))
+        .expect("Left space is a bulky five");
+    assert_eq!(bulky.vital_point, Some(pos(1, 1)));
+
+    let board = board_from_rows(&[
+        ". . B . . . .",
+        ". . B . . . .",
+        "B B B . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+    ]);
+    let spaces = eye_spaces(&board, Stone::Black);
+    let square = spaces
+        .iter()
+        .find(|s| s.shape == Some(EyeShape::SquareFour))
+        .expect("Corner space is a square four");
+    assert_eq!(square.vital_point, None);
+    assert_eq!(EyeShape::SquareFour.status(), ShapeStatus::Dead);
+}
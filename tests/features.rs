@@ -0,0 +1,191 @@
+use betago::ai::features::{self, PLANES};
+use betago::ai::playout::{self, PlayoutPolicy};
+use betago::core::board::MOVE_HISTORY;
+use betago::core::seed;
+use betago::tactics::ladder;
+use betago::{Board, Position, Stone};
+use std::path::Path;
+
+fn plane_name(plane: usize) -> String {
+    match plane {
+        features::OWN_STONES => "own stones".to_string(),
+        features::OPPONENT_STONES => "opponent stones".to_string(),
+        features::EMPTY => "empty".to_string(),
+        p if (features::LIBERTIES..features::LIBERTIES + 4).contains(&p) => {
+            format!("liberties {}", p - features::LIBERTIES + 1)
+        }
+        features::SELF_ATARI => "self-atari".to_string(),
+        features::ATARI => "atari".to_string(),
+        features::KO => "ko".to_string(),
+        features::LEGAL => "legal".to_string(),
+        features::LADDER_CAPTURE => "ladder capture".to_string(),
+        features::LADDER_ESCAPE => "ladder escape".to_string(),
+        p if (features::HISTORY..features::HISTORY + MOVE_HISTORY).contains(&p) => {
+            format!("move -{}", p - features::HISTORY + 1)
+        }
+        features::BLACK_TO_PLAY => "black to play".to_string(),
+        features::ONES => "ones".to_string(),
+        _ => unreachable!("plane {plane}"),
+    }
+}
+
+/// One block per plane, `1` and `.` for set and clear points.
+fn render(planes: &[f32], size: usize) -> String {
+    let area = size * size;
+    assert_eq!(planes.len(), PLANES * area);
+    let mut text = String::new();
+    for (plane, values) in planes.chunks_exact(area).enumerate() {
+        text += &format!("# {plane} {}\n", plane_name(plane));
+        for row in values.chunks_exact(size) {
+            let row: Vec<&str> = row
+                .iter()
+                .map(|&v| match v {
+                    0.0 => ".",
+                    1.0 => "1",
+                    _ => panic!("planes are binary, got {v}"),
+                })
+                .collect();
+            text += &row.join(" ");
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Compares with `tests/golden/<name>.txt`; `UPDATE_GOLDEN=1` rewrites it.
+fn check_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{}: {e}; run with UPDATE_GOLDEN=1", path.display()));
+    assert!(
+        expected == actual,
+        "{name} features changed; run with UPDATE_GOLDEN=1 if intended\n{actual}"
+    );
+}
+
+/// Plays `moves` alternately, Black first.
+fn board_after(size: usize, moves: &[(usize, usize)]) -> Board {
+    let mut board = Board::new(size);
+    let mut player = Stone::Black;
+    for &(x, y) in moves {
+        board.play(Position { x, y }, player).unwrap();
+        player = player.opposite();
+    }
+    board
+}
+
+#[test]
+fn ko_fight_matches_golden() {
+    // Black takes the ko at (3, 2); White may not retake at (2, 2) at once.
+    let board = board_after(
+        7,
+        &[
+            (2, 1),
+            (3, 1),
+            (1, 2),
+            (4, 2),
+            (2, 3),
+            (3, 3),
+            (5, 5),
+            (2, 2),
+            (3, 2),
+        ],
+    );
+    check_golden(
+        "features_ko",
+        &render(&features::encode(&board, Stone::White), 7),
+    );
+}
+
+#[test]
+fn ladder_matches_golden() {
+    // The White stone at (6, 2) breaks the ladder running up and right, so
+    // only the atari from the right captures.
+    let board = board_after(9, &[(3, 2), (3, 3), (2, 3), (6, 2), (4, 4), (0, 8)]);
+    check_golden(
+        "features_ladder",
+        &render(&features::encode(&board, Stone::Black), 9),
+    );
+}
+
+#[test]
+fn encoding_follows_the_side_to_move() {
+    let board = board_after(5, &[(2, 2), (1, 1)]);
+    let black = features::encode(&board, Stone::Black);
+    let white = features::encode(&board, Stone::White);
+    let area = 25;
+    let plane = |planes: &[f32], plane: usize| planes[plane * area..(plane + 1) * area].to_vec();
+    assert_eq!(
+        plane(&black, features::OWN_STONES),
+        plane(&white, features::OPPONENT_STONES)
+    );
+    assert!(
+        plane(&black, features::BLACK_TO_PLAY)
+            .iter()
+            .all(|&v| v == 1.0)
+    );
+    assert!(
+        plane(&white, features::BLACK_TO_PLAY)
+            .iter()
+            .all(|&v| v == 0.0)
+    );
+}
+
+#[test]
+fn passes_age_the_move_history() {
+    let mut board = board_after(5, &[(2, 2)]);
+    board.pass();
+    let planes = features::encode(&board, Stone::Black);
+    let area = 25;
+    let latest = &planes[features::HISTORY * area..(features::HISTORY + 1) * area];
+    let before = &planes[(features::HISTORY + 1) * area..(features::HISTORY + 2) * area];
+    assert!(latest.iter().all(|&v| v == 0.0), "The pass has no point");
+    assert_eq!(before[12], 1.0);
+}
+
+#[test]
+fn move_planes_match_playing_every_point() {
+    let policy = PlayoutPolicy::light();
+    let mut rng = seed::rng(11);
+    for moves in [10, 30, 60, 120] {
+        let mut board = Board::new(9);
+        playout::playout(&mut board, Stone::Black, &policy, moves, &mut rng);
+        for player in [Stone::Black, Stone::White] {
+            let planes = features::encode(&board, player);
+            let plane = |plane: usize, index: usize| planes[plane * 81 + index] == 1.0;
+            for index in 0..81 {
+                let pos = Position {
+                    x: index % 9,
+                    y: index / 9,
+                };
+                if board.get_stone(pos).unwrap().is_some() {
+                    continue;
+                }
+                let mut after = board.clone();
+                let outcome = after.play(pos, player);
+                let self_atari =
+                    outcome.is_ok() && after.count_liberties(&after.get_group(pos)) == 1;
+                let atari = outcome.as_ref().is_ok_and(|outcome| outcome.atari);
+                assert_eq!(plane(features::LEGAL, index), outcome.is_ok(), "{pos:?}");
+                assert_eq!(plane(features::SELF_ATARI, index), self_atari, "{pos:?}");
+                assert_eq!(plane(features::ATARI, index), atari, "{pos:?}");
+                assert_eq!(
+                    plane(features::LADDER_CAPTURE, index),
+                    ladder::is_ladder_capture(&board, pos, player),
+                    "{pos:?}"
+                );
+                assert_eq!(
+                    plane(features::LADDER_ESCAPE, index),
+                    ladder::is_ladder_escape(&board, pos, player),
+                    "{pos:?}"
+                );
+            }
+        }
+    }
+}
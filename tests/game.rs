@@ -0,0 +1,90 @@
+use betago::core::Rules;
+use betago::core::game::{Game, handicap_points};
+use betago::{Position, Stone};
+use rstest::rstest;
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+#[test]
+fn tracks_last_move_and_move_numbers() {
+    let mut game = Game::new(5);
+    assert_eq!(game.last_move(), None);
+
+    game.make_move(pos(1, 0)).unwrap();
+    game.make_move(pos(0, 0)).unwrap();
+    game.make_move(pos(0, 1)).unwrap();
+
+    assert_eq!(game.last_move(), Some(pos(0, 1)));
+    assert_eq!(game.move_number(pos(1, 0)), Some(1));
+    assert_eq!(game.move_number(pos(0, 0)), None);
+    assert_eq!(game.move_number(pos(0, 1)), Some(3));
+
+    game.pass();
+    assert_eq!(game.last_move(), None);
+
+    game.reset();
+    assert_eq!(game.move_number(pos(1, 0)), None);
+}
+
+#[test]
+fn handicap_stones_are_placed_and_white_starts() {
+    let game = Game::with_handicap(19, Rules::Japanese, 0.5, 4);
+
+    assert_eq!(game.handicap().len(), 4);
+    assert_eq!(game.current_player(), Stone::White);
+    for &point in game.handicap() {
+        assert_eq!(game.stone_at(point).unwrap(), Some(Stone::Black));
+    }
+}
+
+#[rstest]
+#[case::taken_twice(vec![pos(2, 2), pos(2, 2)])]
+#[case::off_the_board(vec![pos(2, 2), pos(9, 0)])]
+fn handicap_stones_that_cannot_be_placed_are_errors(#[case] stones: Vec<Position>) {
+    assert!(Game::with_handicap_stones(9, Rules::Chinese, 0.5, stones).is_err());
+}
+
+#[rstest]
+#[case::none(19, 0, 0)]
+#[case::one_stone_is_no_stones(19, 1, 0)]
+#[case::two(19, 2, 2)]
+#[case::five_has_center(19, 5, 5)]
+#[case::nine(19, 9, 9)]
+#[case::capped_on_nine(9, 12, 9)]
+#[case::even_board_only_corners(10, 6, 4)]
+fn places_standard_handicap(#[case] size: usize, #[case] stones: usize, #[case] expected: usize) {
+    let points = handicap_points(size, stones);
+    assert_eq!(points.len(), expected);
+
+    let center = Position {
+        x: size / 2,
+        y: size / 2,
+    };
+    assert_eq!(points.contains(&center), expected % 2 == 1);
+}
+
+#[test]
+fn reset_keeps_handicap() {
+    let mut game = Game::with_handicap(9, Rules::Chinese, 0.5, 2);
+    game.make_move(pos(4, 4)).unwrap();
+    game.reset();
+
+    assert_eq!(game.handicap().len(), 2);
+    assert_eq!(game.stone_at(pos(4, 4)).unwrap(), None);
+    assert_eq!(game.current_player(), Stone::White);
+}
+
+#[test]
+fn only_consecutive_passes_end_the_game() {
+    let mut game = Game::new(5);
+    game.make_move(pos(2, 2)).unwrap();
+    game.pass();
+    game.make_move(pos(1, 1)).unwrap();
+    game.pass();
+    assert_eq!(game.is_game_over(), None);
+
+    game.pass();
+    assert!(game.is_game_over().is_some());
+}
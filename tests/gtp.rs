@@ -0,0 +1,160 @@
+mod common;
+
+use betago::ai::mcts::MctsConfig;
+use betago::ai::network::{Architecture, Network};
+use betago::gtp::{Engine, Gtp, format_vertex, parse_vertex};
+use betago::{Position, Stone};
+use common::pos;
+use rstest::rstest;
+
+fn gtp() -> Gtp {
+    let config = MctsConfig {
+        playouts: 50,
+        memory_mb: 4,
+        ..MctsConfig::default()
+    };
+    Gtp::new(Engine::Mcts, config, 9)
+}
+
+#[rstest]
+#[case("A1", pos(0, 18))]
+#[case("t19", pos(18, 0))]
+#[case("J10", pos(8, 9))]
+fn vertices_skip_i_and_count_rows_from_the_bottom(
+    #[case] vertex: &str,
+    #[case] expected: Position,
+) {
+    assert_eq!(parse_vertex(vertex, 19), Ok(Some(expected)));
+    assert_eq!(
+        format_vertex(Some(expected), 19),
+        vertex.to_ascii_uppercase()
+    );
+}
+
+#[rstest]
+#[case("I5")]
+#[case("A0")]
+#[case("K10")]
+#[case("4A")]
+fn rejects_vertices_off_the_board(#[case] vertex: &str) {
+    assert!(parse_vertex(vertex, 9).is_err());
+}
+
+#[test]
+fn answers_with_the_command_id() {
+    let mut gtp = gtp();
+    assert_eq!(
+        gtp.handle("7 protocol_version").as_deref(),
+        Some("=7 2\n\n")
+    );
+    assert_eq!(
+        gtp.handle("frobnicate").as_deref(),
+        Some("? unknown command\n\n")
+    );
+    assert_eq!(gtp.handle("  # a comment"), None);
+    assert_eq!(
+        gtp.handle("known_command genmove").as_deref(),
+        Some("= true\n\n")
+    );
+}
+
+#[test]
+fn plays_a_game_through_the_protocol() {
+    let mut gtp = gtp();
+    for command in ["boardsize 7", "komi 0.5", "clear_board", "play b D4"] {
+        assert!(gtp.handle(command).unwrap().starts_with('='), "{command}");
+    }
+    assert_eq!(
+        gtp.board().get_stone(pos(3, 3)).unwrap(),
+        Some(Stone::Black)
+    );
+    assert_eq!(
+        gtp.handle("play w D4").as_deref(),
+        Some("? illegal move\n\n")
+    );
+
+    let reply = gtp.handle("genmove w").unwrap();
+    let vertex = reply.trim().trim_start_matches("= ");
+    if let Some(pos) = parse_vertex(vertex, 7).unwrap() {
+        assert_eq!(gtp.board().get_stone(pos).unwrap(), Some(Stone::White));
+    }
+
+    gtp.handle("quit");
+    assert!(gtp.is_done());
+}
+
+#[test]
+fn ponders_between_commands_when_enabled() {
+    let mut gtp = gtp();
+    for command in ["boardsize 5", "genmove b"] {
+        assert!(gtp.handle(command).unwrap().starts_with('='), "{command}");
+    }
+    assert!(!gtp.is_pondering(), "Pondering is off by default");
+
+    for command in ["betago-ponder on", "genmove w"] {
+        assert!(gtp.handle(command).unwrap().starts_with('='), "{command}");
+    }
+    assert!(gtp.is_pondering(), "Ponders on the opponent's time");
+    assert!(gtp.handle("showboard").unwrap().starts_with('='));
+    assert!(gtp.is_pondering(), "Showing the board keeps pondering");
+    assert!(gtp.handle("play b C3").unwrap().starts_with('='));
+    assert!(!gtp.is_pondering(), "The next move stops it");
+}
+
+#[test]
+fn illegal_moves_leave_the_game_unchanged() {
+    let mut gtp = gtp();
+    for command in ["boardsize 7", "play b D4"] {
+        assert!(gtp.handle(command).unwrap().starts_with('='), "{command}");
+    }
+    assert_eq!(
+        gtp.handle("play b D4").as_deref(),
+        Some("? illegal move\n\n")
+    );
+    // The rejected move leaves White to play.
+    for command in ["play w C3", "play b E5"] {
+        assert!(gtp.handle(command).unwrap().starts_with('='), "{command}");
+    }
+}
+
+#[rstest]
+#[case(&["play b pass", "play b D4"])]
+#[case(&["play b pass", "play w pass", "play b D4"])]
+fn passes_never_end_the_game(#[case] commands: &[&str]) {
+    let mut gtp = gtp();
+    for command in commands {
+        assert_eq!(gtp.handle(command).as_deref(), Some("= \n\n"), "{command}");
+    }
+    assert_eq!(
+        gtp.board().get_stone(pos(3, 15)).unwrap(),
+        Some(Stone::Black)
+    );
+    assert!(gtp.handle("genmove w").unwrap().starts_with("= "));
+}
+
+#[test]
+fn loaded_weights_fix_the_board_size() {
+    let network = Network::random(
+        Architecture {
+            channels: 4,
+            blocks: 1,
+            ..Architecture::new(9)
+        },
+        3,
+    );
+    let config = MctsConfig {
+        playouts: 20,
+        memory_mb: 4,
+        ..MctsConfig::default()
+    };
+    let mut gtp = Gtp::with_weights(network, config, 5);
+    assert_eq!(gtp.board().size(), 9);
+
+    assert_eq!(
+        gtp.handle("boardsize 19").as_deref(),
+        Some("? unacceptable size\n\n")
+    );
+    assert_eq!(gtp.handle("boardsize 9").as_deref(), Some("= \n\n"));
+    let reply = gtp.handle("genmove b").unwrap();
+    assert!(reply.starts_with("= "), "{reply}");
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use betago::Stone;
+use betago::tactics::ladder;
+use common::{board_from_rows, pos};
+use rstest::rstest;
+
+/// White's stone at (3, 3) can be chased towards either corner.
+fn chase(breaker: Option<(usize, usize)>) -> betago::Board {
+    let mut board = board_from_rows(&[
+        ". . . . . . . . .",
+        ". . . . . . . . .",
+        ". . . B . . . . .",
+        ". . B W . . . . .",
+        ". . . . B . . . .",
+        ". . . . . . . . .",
+        ". . . . . . . . .",
+        ". . . . . . . . .",
+        ". . . . . . . . .",
+    ]);
+    if let Some((x, y)) = breaker {
+        board.place_stone(pos(x, y), Stone::White).unwrap();
+    }
+    board
+}
+
+#[rstest]
+#[case(None, true)]
+#[case(Some((6, 2)), false)]
+#[case(Some((4, 1)), false)]
+#[case(Some((7, 0)), true)]
+fn breakers_on_the_path_stop_the_ladder(
+    #[case] breaker: Option<(usize, usize)>,
+    #[case] captured: bool,
+) {
+    let board = chase(breaker);
+    assert_eq!(
+        ladder::is_ladder_capture(&board, pos(3, 4), Stone::Black),
+        captured
+    );
+    assert!(
+        ladder::is_ladder_capture(&board, pos(4, 3), Stone::Black),
+        "The other way is always open"
+    );
+}
+
+#[test]
+fn running_out_of_a_broken_ladder_escapes() {
+    let mut board = chase(Some((6, 2)));
+    board.place_stone(pos(3, 4), Stone::Black).unwrap();
+    assert!(ladder::is_ladder_escape(&board, pos(4, 3), Stone::White));
+    assert!(!ladder::is_captured(&board, pos(3, 3), Stone::White));
+
+    let mut board = chase(None);
+    board.place_stone(pos(3, 4), Stone::Black).unwrap();
+    assert!(!ladder::is_ladder_escape(&board, pos(4, 3), Stone::White));
+    assert!(ladder::is_captured(&board, pos(3, 3), Stone::White));
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use betago::tactics::life_death::{Solver, Status, solve};
+use betago::*;
+use common::{board_from_rows, pos};
+use rstest::rstest;
+
+fn straight_three() -> Board {
+    board_from_rows(&[
+        ". . . B W",
+        "B B B B W",
+        "W W W W W",
+        ". . . . .",
+        ". . . . .",
+    ])
+}
+
+#[test]
+fn straight_three_lives_with_defender_to_play() {
+    let board = straight_three();
+    let region = [pos(0, 0), pos(1, 0), pos(2, 0)];
+
+    let solution = solve(&board, pos(3, 0), &region, Stone::Black);
+    assert_eq!(solution.status, Status::Alive, "Black should make two eyes");
+    assert_eq!(solution.key_move, Some(pos(1, 0)), "Vital point is the middle");
+}
+
+#[test]
+fn straight_three_dies_with_attacker_to_play() {
+    let board = straight_three();
+    let region = [pos(0, 0), pos(1, 0), pos(2, 0)];
+
+    let solution = solve(&board, pos(3, 0), &region, Stone::White);
+    assert_eq!(solution.status, Status::Dead, "White should kill the group");
+    assert_eq!(solution.key_move, Some(pos(1, 0)), "Vital point is the middle");
+    assert_eq!(solution.main_line.first(), Some(&Some(pos(1, 0))));
+}
+
+#[test]
+fn two_point_eye_is_dead() {
+    let board = board_from_rows(&[
+        ". . B W .",
+        "B B B W .",
+        "W W W W .",
+        ". . . . .",
+        ". . . . .",
+    ]);
+    let region = [pos(0, 0), pos(1, 0)];
+
+    let solution = solve(&board, pos(2, 0), &region, Stone::Black);
+    assert_eq!(solution.status, Status::Dead, "Two-point eye can't live");
+    assert_eq!(solution.key_move, None, "Losing side has no key move");
+}
+
+#[test]
+fn two_eyes_live_without_a_move() {
+    let board = board_from_rows(&[
+        ". B . B W",
+        "B B B B W",
+        "W W W W W",
+        ". . . . .",
+        ". . . . .",
+    ]);
+    let region = [pos(0, 0), pos(2, 0)];
+
+    let solution = solve(&board, pos(1, 0), &region, Stone::White);
+    assert_eq!(solution.status, Status::Alive, "Two separate eyes are alive");
+}
+
+#[test]
+fn fights_deeper_than_the_limit_stay_unknown() {
+    let board = straight_three();
+    let region = [pos(0, 0), pos(1, 0), pos(2, 0)];
+
+    let solution = Solver::new(10_000, 1).solve(&board, pos(3, 0), &region, Stone::White);
+    assert_eq!(solution.status, Status::Unknown, "One move can't settle it");
+    assert_eq!(solution.key_move, None);
+    assert!(
+        solution.nodes < 100,
+        "Gives up without spending the node budget, used {}",
+        solution.nodes
+    );
+}
+
+#[rstest]
+#[case(pos(0, 0))]
+#[case(pos(5, 5))]
+fn targets_without_a_group_are_unknown(#[case] target: Position) {
+    let board = straight_three();
+    let region = [pos(0, 0), pos(1, 0), pos(2, 0)];
+
+    let solution = solve(&board, target, &region, Stone::White);
+    assert_eq!(solution.status, Status::Unknown);
+    assert_eq!(solution.key_move, None);
+}
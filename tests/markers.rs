@@ -0,0 +1,35 @@
+use betago::Position;
+use betago::gui::markers::{coordinate_label, hoshi_points};
+use rstest::rstest;
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+#[rstest]
+#[case::top_left(pos(0, 0), 19, "A19")]
+#[case::skips_i(pos(8, 18), 19, "J1")]
+#[case::last_column(pos(18, 0), 19, "T19")]
+#[case::small_board(pos(4, 4), 9, "E5")]
+fn formats_coordinates(#[case] point: Position, #[case] size: usize, #[case] expected: &str) {
+    assert_eq!(coordinate_label(point, size), expected);
+}
+
+#[rstest]
+#[case::tiny(5, 0)]
+#[case::nine(9, 5)]
+#[case::thirteen(13, 5)]
+#[case::nineteen(19, 9)]
+fn places_hoshi(#[case] size: usize, #[case] count: usize) {
+    let points = hoshi_points(size);
+    assert_eq!(points.len(), count);
+    assert!(points.iter().all(|p| p.x < size && p.y < size));
+}
+
+#[test]
+fn nineteen_has_standard_hoshi() {
+    let points = hoshi_points(19);
+    for point in [pos(3, 3), pos(9, 9), pos(15, 3), pos(3, 9), pos(15, 15)] {
+        assert!(points.contains(&point));
+    }
+}
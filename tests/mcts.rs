@@ -0,0 +1,267 @@
+mod common;
+
+use betago::Stone;
+use betago::ai::mcts::{MctsAI, MctsConfig, Parallelism, position_key};
+use betago::ai::transposition::{Edge, Node, Replacement, TranspositionTable};
+use common::{board_from_rows, pos};
+
+fn config(playouts: usize, transpositions: bool) -> MctsConfig {
+    MctsConfig {
+        playouts,
+        transpositions,
+        memory_mb: 16,
+        ..MctsConfig::default()
+    }
+}
+
+fn node(key: u64, visits: u32) -> Node {
+    Node {
+        key,
+        generation: 0,
+        visits,
+        edges: vec![Edge::new(None)],
+    }
+}
+
+#[test]
+fn spends_every_playout_below_the_root() {
+    let board = board_from_rows(&[
+        ". . . . .",
+        ". . B . .",
+        ". B W B .",
+        ". B W B .",
+        ". . . . .",
+    ]);
+
+    let result = MctsAI::new(config(500, true), 1).search(&board, Stone::Black);
+    assert_eq!(result.stats.playouts, 500);
+    assert_eq!(
+        result.moves.iter().map(|edge| edge.visits).sum::<u32>(),
+        499,
+        "The first playout expands the root"
+    );
+    assert!(
+        result
+            .moves
+            .windows(2)
+            .all(|pair| pair[0].visits >= pair[1].visits)
+    );
+}
+
+#[test]
+fn searches_are_reproducible_from_the_seed() {
+    let board = betago::Board::new(7);
+    let search = || MctsAI::new(config(300, true), 5).search(&board, Stone::Black);
+    assert_eq!(search(), search());
+}
+
+#[test]
+fn threads_share_the_playout_budget() {
+    let board = betago::Board::new(7);
+    let config = MctsConfig {
+        threads: 4,
+        virtual_loss: 3,
+        ..config(800, true)
+    };
+    let result = MctsAI::new(config, 2).search(&board, Stone::Black);
+    assert_eq!(result.stats.playouts, 800);
+    let visits: u32 = result.moves.iter().map(|edge| edge.visits).sum();
+    assert!(
+        (796..800).contains(&visits),
+        "Only threads that found the root missing skip it, and no virtual loss is left: {visits}"
+    );
+}
+
+#[test]
+fn root_parallel_threads_merge_their_root_moves() {
+    let board = betago::Board::new(7);
+    let config = MctsConfig {
+        threads: 3,
+        parallelism: Parallelism::Root,
+        ..config(600, true)
+    };
+    let result = MctsAI::new(config, 4).search(&board, Stone::Black);
+    assert_eq!(result.stats.playouts, 600);
+    assert_eq!(
+        result.moves.iter().map(|edge| edge.visits).sum::<u32>(),
+        597,
+        "Every thread expands its own root"
+    );
+    assert_eq!(result.moves.len(), 7 * 7 + 1, "Each move appears once");
+}
+
+#[test]
+fn transpositions_share_nodes() {
+    let board = betago::Board::new(5);
+    let dag = MctsAI::new(config(2000, true), 3).search(&board, Stone::Black);
+    let tree = MctsAI::new(config(2000, false), 3).search(&board, Stone::Black);
+
+    assert!(dag.stats.transpositions > 0);
+    assert!(
+        tree.stats.transpositions > 0,
+        "The tree stores some positions twice"
+    );
+    assert!(dag.stats.nodes <= dag.stats.playouts);
+
+    // With few points left, the tree keeps storing positions the DAG has.
+    let endgame = board_from_rows(&[
+        ". . . . .",
+        "B B B B B",
+        "B W W W B",
+        "W W . W W",
+        ". W W W .",
+    ]);
+    let dag = MctsAI::new(config(1000, true), 3).search(&endgame, Stone::Black);
+    let tree = MctsAI::new(config(1000, false), 3).search(&endgame, Stone::Black);
+    assert!(
+        dag.stats.nodes < tree.stats.nodes,
+        "{} shared nodes against {} in the tree",
+        dag.stats.nodes,
+        tree.stats.nodes
+    );
+    assert!(dag.stats.memory < tree.stats.memory);
+}
+
+#[test]
+fn keys_cover_side_to_move_passes_and_ko() {
+    let board = betago::Board::new(7);
+    let black = position_key(&board, Stone::Black, 0);
+    assert_ne!(black, position_key(&board, Stone::White, 0));
+    assert_ne!(black, position_key(&board, Stone::Black, 1));
+
+    let mut ko = board_from_rows(&[
+        ". B W . . . .",
+        "B . B W . . .",
+        ". B W . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+    ]);
+    ko.place_stone(pos(1, 1), Stone::White).unwrap();
+    assert_eq!(ko.ko_point(Stone::Black), Some(pos(2, 1)));
+    assert_ne!(
+        position_key(&ko, Stone::Black, 0),
+        ko.hash(),
+        "Ko state changes the key"
+    );
+}
+
+#[test]
+fn table_respects_its_capacity_and_replacement_policy() {
+    let table = TranspositionTable::new(2, Replacement::PreferVisited);
+    assert!(table.insert(node(2, 5)));
+    assert!(table.insert(node(4, 3)));
+    assert!(!table.insert(node(6, 0)), "Both slots are worth more");
+    assert!(!table.insert(node(2, 0)), "Key is already stored");
+    assert_eq!(table.len(), 2);
+
+    table.new_generation();
+    assert!(table.insert(node(6, 0)), "Old searches make way");
+    assert_eq!(table.with(4, |n| n.visits), None);
+    assert_eq!(table.with(2, |n| n.visits), Some(5));
+
+    let always = TranspositionTable::new(2, Replacement::Always);
+    always.insert(node(2, 5));
+    always.insert(node(4, 3));
+    assert!(always.insert(node(6, 0)));
+    assert_eq!(always.len(), 2);
+    assert!(always.with(6, |_| ()).is_some());
+
+    let pinned = TranspositionTable::new(2, Replacement::Always);
+    pinned.pin(2);
+    pinned.insert(node(2, 0));
+    pinned.insert(node(4, 3));
+    assert!(pinned.insert(node(6, 0)));
+    assert!(
+        pinned.with(2, |_| ()).is_some(),
+        "The root is never replaced"
+    );
+    assert!(pinned.with(4, |_| ()).is_none());
+
+    let limited = TranspositionTable::with_memory(1 << 20, 64, Replacement::default());
+    assert!(limited.capacity() * 64 * size_of::<Edge>() <= 1 << 20);
+}
+
+#[test]
+fn rave_collects_amaf_statistics() {
+    let board = betago::Board::new(5);
+    let rave = MctsConfig {
+        rave: true,
+        ..config(300, true)
+    };
+
+    let result = MctsAI::new(rave, 2).search(&board, Stone::Black);
+    let visits: u32 = result.moves.iter().map(|edge| edge.visits).sum();
+    let amaf: u32 = result.moves.iter().map(|edge| edge.amaf_visits).sum();
+    assert!(amaf > visits, "Every playout updates many moves");
+    assert!(
+        result
+            .moves
+            .iter()
+            .all(|edge| edge.amaf_wins <= edge.amaf_visits as f32)
+    );
+
+    let plain = MctsAI::new(config(300, true), 2).search(&board, Stone::Black);
+    assert!(plain.moves.iter().all(|edge| edge.amaf_visits == 0));
+}
+
+#[test]
+fn rave_does_not_pass_on_an_empty_board() {
+    let board = betago::Board::new(7);
+    let rave = MctsConfig {
+        rave: true,
+        ..config(500, true)
+    };
+
+    let result = MctsAI::new(rave, 4).search(&board, Stone::Black);
+    assert!(result.best.is_some(), "Passing gives the board away");
+}
+
+#[test]
+fn reuses_the_subtree_after_the_opponent_replies() {
+    let board = betago::Board::new(5);
+    let searched = |reuse_tree| {
+        let mut ai = MctsAI::new(
+            MctsConfig {
+                reuse_tree,
+                ..config(400, true)
+            },
+            6,
+        );
+        let black = ai.search(&board, Stone::Black).best.unwrap();
+        let mut after = board.clone();
+        after.play(black, Stone::Black).unwrap();
+        let white = ai.search(&after, Stone::White).best.unwrap();
+        after.play(white, Stone::White).unwrap();
+        let result = ai.search(&after, Stone::Black);
+        result.moves.iter().map(|edge| edge.visits).sum::<u32>()
+    };
+    assert_eq!(searched(false), 399);
+    assert!(
+        searched(true) > 400,
+        "The earlier searches grew this position"
+    );
+}
+
+#[test]
+fn pondering_grows_the_tree_until_stopped() {
+    let board = betago::Board::new(5);
+    let mut ai = MctsAI::new(
+        MctsConfig {
+            ponder: true,
+            ..config(100, true)
+        },
+        8,
+    );
+    ai.ponder(&board, Stone::White);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    ai.stop_pondering();
+    assert!(!ai.is_pondering());
+
+    // The search adds its own 100 playouts to the pondered tree.
+    let result = ai.search(&board, Stone::White);
+    assert_eq!(result.stats.playouts, 100);
+    let visits: u32 = result.moves.iter().map(|edge| edge.visits).sum();
+    assert!(visits > 100, "Only {visits} root visits");
+}
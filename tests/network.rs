@@ -0,0 +1,261 @@
+use betago::ai::features::PLANES;
+use betago::ai::mcts::{MctsAI, MctsConfig, SearchResult, SearchStats};
+use betago::ai::network::{Architecture, Evaluation, Evaluator, Network, NetworkAI, NetworkError};
+use betago::ai::transposition::Edge;
+use betago::{Board, Position, Stone};
+use std::sync::{Arc, Mutex};
+
+const FAVOURITE: Position = Position { x: 3, y: 1 };
+
+/// Puts most of the policy on one point, calls every position even and
+/// records the batch sizes it was asked for.
+#[derive(Default)]
+struct Stub {
+    batches: Mutex<Vec<usize>>,
+}
+
+impl Evaluator for Stub {
+    fn evaluate(&self, batch: &[(&Board, Stone)]) -> Vec<Evaluation> {
+        self.batches.lock().unwrap().push(batch.len());
+        batch
+            .iter()
+            .map(|(board, _)| {
+                let points = board.size() * board.size();
+                let mut policy = vec![0.1 / points as f32; points + 1];
+                policy[board.pos_to_index(FAVOURITE)] += 0.9;
+                Evaluation { policy, value: 0.0 }
+            })
+            .collect()
+    }
+}
+
+fn config(playouts: usize) -> MctsConfig {
+    MctsConfig {
+        playouts,
+        memory_mb: 4,
+        ..MctsConfig::default()
+    }
+}
+
+#[test]
+fn network_outputs_a_distribution_and_a_bounded_value() {
+    let architecture = Architecture {
+        channels: 8,
+        blocks: 2,
+        ..Architecture::new(7)
+    };
+    let network = Network::random(architecture, 3);
+    let mut board = Board::new(7);
+    board.play(Position { x: 3, y: 3 }, Stone::Black).unwrap();
+
+    let evaluation = network.forward(&board, Stone::White);
+    assert_eq!(evaluation.policy.len(), 7 * 7 + 1);
+    assert!((evaluation.policy.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    assert!((-1.0..=1.0).contains(&evaluation.value));
+    assert_eq!(
+        Network::random(architecture, 3).evaluate(&[(&board, Stone::White)]),
+        vec![evaluation]
+    );
+}
+
+#[test]
+fn puct_follows_the_policy_prior() {
+    let stub = Arc::new(Stub::default());
+    let mut ai = MctsAI::with_network(config(200), stub.clone(), 1);
+    let result = ai.search(&Board::new(5), Stone::Black);
+    assert_eq!(result.best, Some(FAVOURITE));
+    assert_eq!(result.stats.playouts, 200);
+}
+
+#[test]
+fn leaves_are_evaluated_in_batches() {
+    let stub = Arc::new(Stub::default());
+    let config = MctsConfig {
+        batch_size: 8,
+        virtual_loss: 3,
+        ..config(64)
+    };
+    MctsAI::with_network(config, stub.clone(), 2).search(&Board::new(5), Stone::Black);
+
+    let batches = stub.batches.lock().unwrap();
+    assert!(batches.iter().all(|&n| n <= 8));
+    assert!(batches.len() <= 64 / 8 + 1, "{batches:?}");
+    assert!(batches.iter().any(|&n| n > 1));
+}
+
+#[test]
+fn root_noise_perturbs_the_priors() {
+    let board = Board::new(5);
+    let priors = |noise_fraction| {
+        let config = MctsConfig {
+            noise_fraction,
+            ..config(20)
+        };
+        let result =
+            MctsAI::with_network(config, Arc::new(Stub::default()), 3).search(&board, Stone::Black);
+        let mut priors: Vec<(Option<Position>, f32)> = result
+            .moves
+            .iter()
+            .map(|edge| (edge.mv, edge.prior))
+            .collect();
+        priors.sort_by_key(|&(mv, _)| mv.map(|pos| (pos.y, pos.x)));
+        priors
+    };
+
+    let clean = priors(0.0);
+    let noisy = priors(0.25);
+    assert!((noisy.iter().map(|p| p.1).sum::<f32>() - 1.0).abs() < 1e-4);
+    assert_ne!(clean, noisy);
+}
+
+#[test]
+fn root_noise_does_not_pile_up_on_a_reused_root() {
+    let board = Board::new(5);
+    let fraction = 0.25;
+    let config = MctsConfig {
+        noise_fraction: fraction,
+        reuse_tree: true,
+        ..config(20)
+    };
+    let mut ai = MctsAI::with_network(config, Arc::new(Stub::default()), 3);
+    for _ in 0..10 {
+        let result = ai.search(&board, Stone::Black);
+        let favourite = result
+            .moves
+            .iter()
+            .find(|edge| edge.mv == Some(FAVOURITE))
+            .unwrap();
+        assert!(favourite.policy > 0.9);
+        assert!(
+            favourite.prior >= (1.0 - fraction as f32) * favourite.policy,
+            "Noise replaces the last search's noise, prior {}",
+            favourite.prior
+        );
+    }
+}
+
+#[test]
+fn temperature_samples_in_proportion_to_visits() {
+    let edge = |x, visits| Edge {
+        visits,
+        ..Edge::new(Some(Position { x, y: 0 }))
+    };
+    let result = SearchResult {
+        best: Some(Position { x: 0, y: 0 }),
+        moves: vec![edge(0, 30), edge(1, 10)],
+        stats: SearchStats::default(),
+    };
+
+    let mut greedy = MctsAI::new(config(1), 4);
+    assert!((0..50).all(|_| greedy.choose(&result) == result.best));
+
+    let mut sampler = MctsAI::new(
+        MctsConfig {
+            temperature: 1.0,
+            ..config(1)
+        },
+        4,
+    );
+    let second = (0..400)
+        .filter(|_| sampler.choose(&result) == Some(Position { x: 1, y: 0 }))
+        .count();
+    assert!((60..140).contains(&second), "{second}");
+}
+
+fn small_network() -> Network {
+    Network::random(
+        Architecture {
+            channels: 4,
+            blocks: 1,
+            value_hidden: 8,
+            ..Architecture::new(5)
+        },
+        11,
+    )
+}
+
+fn weights_bytes(network: &Network) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    network.write(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn weights_survive_a_save_and_load() {
+    let network = small_network();
+    let path = std::env::temp_dir().join(format!("betago-weights-{}.bin", std::process::id()));
+    network.save(&path).unwrap();
+    let loaded = Network::load(&path).unwrap();
+
+    assert_eq!(loaded, network);
+    let board = Board::new(5);
+    assert_eq!(
+        loaded.forward(&board, Stone::Black),
+        network.forward(&board, Stone::Black)
+    );
+
+    assert!(NetworkAI::load(&path, 5, config(10), 1).is_ok());
+    assert!(matches!(
+        NetworkAI::load(&path, 9, config(10), 1),
+        Err(NetworkError::BoardSize {
+            expected: 5,
+            found: 9
+        })
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn malformed_weights_are_reported() {
+    let bytes = weights_bytes(&small_network());
+    let read = |bytes: &[u8]| Network::read(&mut &bytes[..]);
+    let patched = |offset: usize, value: u32| {
+        let mut bytes = bytes.clone();
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        bytes
+    };
+
+    assert!(matches!(read(b"BGOB"), Err(NetworkError::BadMagic)));
+    assert!(matches!(
+        read(&patched(4, 9)),
+        Err(NetworkError::UnsupportedVersion(9))
+    ));
+    assert!(matches!(
+        read(&patched(8, 0)),
+        Err(NetworkError::InvalidArchitecture(_))
+    ));
+    assert!(matches!(
+        read(&patched(12, 17)),
+        Err(NetworkError::PlaneMismatch { found: 17, .. })
+    ));
+    // A bare header claiming a million channels.
+    assert!(matches!(
+        read(&patched(16, 1 << 20)[..32]),
+        Err(NetworkError::InvalidArchitecture(_))
+    ));
+    // One more channel than the tensors were saved with.
+    match read(&patched(16, 5)) {
+        Err(NetworkError::Shape {
+            tensor,
+            expected,
+            found,
+        }) => {
+            assert_eq!(tensor, "input.weights");
+            assert_eq!((expected, found), (5 * 9 * PLANES, 4 * 9 * PLANES));
+        }
+        other => panic!("expected a shape error, got {other:?}"),
+    }
+    match read(&bytes[..bytes.len() - 2]) {
+        Err(NetworkError::Truncated { tensor }) => assert_eq!(tensor, "value.bias"),
+        other => panic!("expected truncated weights, got {other:?}"),
+    }
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(read(&trailing), Err(NetworkError::TrailingData)));
+    let mut infinite = bytes.clone();
+    infinite[32..36].copy_from_slice(&f32::INFINITY.to_le_bytes());
+    assert!(matches!(
+        read(&infinite),
+        Err(NetworkError::NonFinite { tensor }) if tensor == "input.weights"
+    ));
+}
@@ -0,0 +1,39 @@
+use betago::ai::heuristic::HeuristicAI;
+use betago::core::game::Game;
+use betago::gui::overlay::{Analysis, heatmap};
+use betago::{Board, Stone};
+
+#[test]
+fn heatmap_falls_back_to_the_opponents_engine() {
+    let board = Board::new(9);
+    let engine = HeuristicAI::default();
+
+    let own = heatmap(&board, Stone::Black, Some(&engine), None).unwrap();
+    let opponent = heatmap(&board, Stone::Black, None, Some(&engine)).unwrap();
+    assert_eq!(own.len(), 81);
+    assert_eq!(own, opponent, "Either engine reads the position for Black");
+    assert_eq!(heatmap(&board, Stone::Black, None, None), None);
+}
+
+#[test]
+fn analysis_waiting_for_an_engine_is_made_again() {
+    let game = Game::new(9);
+    let hash = game.board_state().hash();
+    let mut analysis = Analysis {
+        hash,
+        to_play: Stone::Black,
+        move_number: 0,
+        ownership: vec![0.0; 81],
+        score: game.score(&[]),
+        move_probabilities: None,
+        heatmap_pending: false,
+    };
+    assert!(analysis.is_current(hash, Stone::Black, 0));
+    assert!(
+        !analysis.is_current(hash, Stone::White, 1),
+        "A pass hands over the move"
+    );
+
+    analysis.heatmap_pending = true;
+    assert!(!analysis.is_current(hash, Stone::Black, 0));
+}
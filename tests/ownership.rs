@@ -0,0 +1,102 @@
+mod common;
+
+use betago::Stone;
+use betago::ai::ownership::{
+    InfluenceEstimator, MonteCarloEstimator, OwnershipEstimator, dead_stones,
+};
+use common::{board_from_rows, pos};
+
+fn split_board() -> betago::Board {
+    board_from_rows(&[
+        ". . B W . . .",
+        ". . B W . . .",
+        ". . B W . . .",
+        ". . B W . W .",
+        ". . B W . . .",
+        ". . B W . . .",
+        ". . B W . . .",
+    ])
+}
+
+#[test]
+fn influence_splits_the_board() {
+    let board = split_board();
+    let ownership = InfluenceEstimator::default().estimate(&board, Stone::Black);
+
+    assert_eq!(ownership.len(), 49);
+    assert!(ownership.iter().all(|v| (-1.0..=1.0).contains(v)));
+    for territory in [pos(1, 0), pos(1, 3), pos(1, 6)] {
+        let value = ownership[board.pos_to_index(territory)];
+        assert!(value > 0.5, "Black territory at {territory:?} is {value}");
+    }
+    for territory in [pos(4, 0), pos(4, 3), pos(6, 3)] {
+        let value = ownership[board.pos_to_index(territory)];
+        assert!(value < -0.5, "White territory at {territory:?} is {value}");
+    }
+    assert!(ownership[board.pos_to_index(pos(6, 0))] < 0.0);
+    assert!(ownership.iter().any(|&v| v.abs() == 1.0));
+    assert!(ownership.iter().any(|&v| v.abs() < 1.0 && v != 0.0));
+}
+
+#[test]
+fn influence_keeps_one_scale_across_positions() {
+    let board = board_from_rows(&[
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . B . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+    ]);
+    let ownership = InfluenceEstimator::default().estimate(&board, Stone::Black);
+
+    assert_eq!(ownership[board.pos_to_index(pos(3, 3))], 1.0);
+    let neighbour = ownership[board.pos_to_index(pos(3, 2))];
+    assert!(
+        (0.0..0.5).contains(&neighbour),
+        "One stone doesn't own its neighbours, got {neighbour}"
+    );
+}
+
+#[test]
+fn monte_carlo_agrees_on_settled_areas() {
+    let board = board_from_rows(&[
+        ". B . . . W .",
+        ". B . . . W .",
+        "B B . . . W W",
+        ". B . . . W .",
+        "B B . . . W W",
+        ". B . . . W .",
+        ". B . . . W .",
+    ]);
+    let estimator = MonteCarloEstimator {
+        playouts: 20,
+        seed: 7,
+    };
+    let ownership = estimator.estimate(&board, Stone::Black);
+
+    assert!(ownership.iter().all(|v| (-1.0..=1.0).contains(v)));
+    assert_eq!(ownership[board.pos_to_index(pos(0, 3))], 1.0);
+    assert_eq!(ownership[board.pos_to_index(pos(1, 6))], 1.0);
+    assert_eq!(ownership[board.pos_to_index(pos(6, 3))], -1.0);
+    assert_eq!(ownership[board.pos_to_index(pos(5, 0))], -1.0);
+}
+
+#[test]
+fn lone_stone_in_enemy_area_is_dead() {
+    let board = board_from_rows(&[
+        ". . B W . . .",
+        ". . B W . . .",
+        ". . B W . . .",
+        ". . B W . . .",
+        ". . B W . B .",
+        ". . B W . . .",
+        ". . B W . . .",
+    ]);
+    let mut ownership = vec![0.0; 49];
+    ownership[board.pos_to_index(pos(5, 4))] = -0.8;
+    ownership[board.pos_to_index(pos(3, 0))] = -0.8;
+
+    assert_eq!(dead_stones(&board, &ownership, 0.5), vec![pos(5, 4)]);
+}
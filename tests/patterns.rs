@@ -0,0 +1,190 @@
+mod common;
+
+use betago::Stone;
+use betago::ai::GoAI;
+use betago::ai::heuristic::HeuristicAI;
+use betago::ai::mcts::{MctsAI, MctsConfig};
+use betago::ai::patterns::{
+    PatternError, PatternLearner, PatternPriors, PatternShape, pattern_key, pattern_keys,
+};
+use betago::sgf::GameTree;
+use common::{board_from_rows, pos};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn white_in_atari() -> betago::Board {
+    board_from_rows(&[
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . B . . .",
+        ". . B W B . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+    ])
+}
+
+#[test]
+fn rotated_and_mirrored_shapes_share_a_key() {
+    let board = board_from_rows(&[
+        ". . . . .",
+        ". B W . .",
+        ". . . . .",
+        ". . . . .",
+        ". . . . .",
+    ]);
+    let mirrored = board_from_rows(&[
+        ". . . . .",
+        ". . W B .",
+        ". . . . .",
+        ". . . . .",
+        ". . . . .",
+    ]);
+    let rotated = board_from_rows(&[
+        ". . . . .",
+        ". . . B .",
+        ". . . W .",
+        ". . . . .",
+        ". . . . .",
+    ]);
+
+    for shape in PatternShape::ALL {
+        let key = pattern_key(&board, pos(1, 2), Stone::Black, shape);
+        assert_eq!(pattern_key(&mirrored, pos(3, 2), Stone::Black, shape), key);
+        assert_eq!(pattern_key(&rotated, pos(2, 1), Stone::Black, shape), key);
+    }
+}
+
+#[test]
+fn keys_see_the_edge_and_the_side_to_move() {
+    let board = white_in_atari();
+
+    assert_ne!(
+        pattern_keys(&board, pos(0, 0), Stone::Black),
+        pattern_keys(&board, pos(3, 0), Stone::Black)
+    );
+    assert_ne!(
+        pattern_keys(&board, pos(0, 3), Stone::Black),
+        pattern_keys(&board, pos(6, 6), Stone::Black),
+        "First line differs from an empty area"
+    );
+    assert_ne!(
+        pattern_keys(&board, pos(3, 4), Stone::Black),
+        pattern_keys(&board, pos(3, 4), Stone::White)
+    );
+}
+
+#[test]
+fn keys_count_liberties() {
+    let atari = white_in_atari();
+    let free = board_from_rows(&[
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . B . . .",
+        ". . B W . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+    ]);
+
+    assert_ne!(
+        pattern_key(&atari, pos(3, 4), Stone::Black, PatternShape::Square),
+        pattern_key(&free, pos(3, 4), Stone::Black, PatternShape::Square),
+    );
+}
+
+#[test]
+fn learning_favours_the_played_pattern() {
+    let board = white_in_atari();
+    let mut learner = PatternLearner::new();
+    for _ in 0..20 {
+        learner.add_position(&board, pos(3, 4), Stone::Black);
+    }
+
+    let priors = learner.train(10);
+    let capture = priors.strength(&board, pos(3, 4), Stone::Black);
+    let elsewhere = priors.strength(&board, pos(6, 6), Stone::Black);
+    assert!(capture > 10.0 * elsewhere, "{capture} vs {elsewhere}");
+
+    let probabilities = priors.move_priors(&board, Stone::Black);
+    let best = probabilities
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(i, _)| i);
+    assert_eq!(best, Some(board.pos_to_index(pos(3, 4))));
+    assert!((probabilities.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn learns_from_game_records() {
+    let tree = GameTree::parse("(;GM[1]SZ[9];B[ee];W[ce];B[gc];W[])").unwrap();
+    let mut learner = PatternLearner::new();
+
+    assert_eq!(learner.add_game(&tree).unwrap(), 3);
+    assert_eq!(learner.positions(), 3);
+    assert!(learner.patterns() > 0);
+
+    let illegal = GameTree::parse("(;GM[1]SZ[9];B[ee];W[ee])").unwrap();
+    assert!(learner.add_game(&illegal).is_err());
+    assert_eq!(learner.positions(), 3, "Broken records add nothing");
+}
+
+#[test]
+fn priors_round_trip_through_a_file() {
+    let board = white_in_atari();
+    let key = pattern_key(&board, pos(3, 4), Stone::Black, PatternShape::Diamond);
+    let priors = PatternPriors::new(HashMap::from([(key, 4.5), (17, 0.25)]));
+
+    let path = std::env::temp_dir().join(format!("betago-patterns-{}.bin", std::process::id()));
+    priors.save(&path).unwrap();
+    let loaded = PatternPriors::load(&path);
+    std::fs::write(&path, b"nope").unwrap();
+    let garbage = PatternPriors::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap(), priors);
+    assert!(matches!(garbage, Err(PatternError::BadMagic)));
+    assert_eq!(priors.gamma(12345), 1.0);
+}
+
+#[test]
+fn heuristic_follows_strong_patterns() {
+    let board = board_from_rows(&[
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . . . . .",
+        ". . . B . . .",
+        ". . . . . . .",
+        ". . . . W . .",
+        ". . . . . . .",
+    ]);
+    let favourite = pos(0, 0);
+    let priors = PatternPriors::new(HashMap::from([(
+        pattern_key(&board, favourite, Stone::Black, PatternShape::Square),
+        1e6,
+    )]));
+
+    let mut ai = HeuristicAI::default().with_patterns(Arc::new(priors), 1.0);
+    assert_eq!(ai.select_move(&board, Stone::Black), Some(favourite));
+}
+
+#[test]
+fn mcts_tries_strong_patterns_first() {
+    let board = white_in_atari();
+    let favourite = pos(3, 4);
+    let priors = PatternPriors::new(HashMap::from([(
+        pattern_key(&board, favourite, Stone::Black, PatternShape::Square),
+        1e6,
+    )]));
+    let config = MctsConfig {
+        playouts: 2,
+        ..MctsConfig::default()
+    };
+
+    let result = MctsAI::new(config, 3)
+        .with_patterns(Arc::new(priors))
+        .search(&board, Stone::Black);
+    assert_eq!(result.moves[0].mv, Some(favourite));
+    assert_eq!(result.moves[0].visits, 1);
+}
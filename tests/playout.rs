@@ -0,0 +1,89 @@
+mod common;
+
+use betago::Stone;
+use betago::ai::playout::{PlayoutPolicy, playout};
+use betago::core::seed;
+use common::{board_from_rows, pos};
+
+fn two_eyes() -> betago::Board {
+    board_from_rows(&[
+        "B B B B B",
+        "B . B B B",
+        "B B B B B",
+        "B B B . B",
+        "B B B B B",
+    ])
+}
+
+fn white_in_atari() -> betago::Board {
+    board_from_rows(&[
+        ". . . . .",
+        ". . B . .",
+        ". B W B .",
+        ". . . . .",
+        ". . . . .",
+    ])
+}
+
+#[test]
+fn light_policy_never_fills_its_own_eyes() {
+    let board = two_eyes();
+    for s in 0..20 {
+        let mut rng = seed::rng(s);
+        assert_eq!(
+            PlayoutPolicy::light().select_move(&board, Stone::Black, &mut rng),
+            None
+        );
+        assert!(
+            PlayoutPolicy::uniform(0.0)
+                .select_move(&board, Stone::Black, &mut rng)
+                .is_some()
+        );
+    }
+}
+
+#[test]
+fn heavy_policy_captures_first() {
+    let board = white_in_atari();
+    for s in 0..20 {
+        assert_eq!(
+            PlayoutPolicy::heavy().select_move(&board, Stone::Black, &mut seed::rng(s)),
+            Some(pos(2, 3))
+        );
+    }
+}
+
+#[test]
+fn heavy_policy_escapes_from_atari() {
+    let board = white_in_atari();
+    for s in 0..20 {
+        assert_eq!(
+            PlayoutPolicy::heavy().select_move(&board, Stone::White, &mut seed::rng(s)),
+            Some(pos(2, 3))
+        );
+    }
+}
+
+#[test]
+fn uniform_policy_passes_at_its_pass_rate() {
+    let board = white_in_atari();
+    let mut rng = seed::rng(3);
+    assert_eq!(
+        PlayoutPolicy::uniform(1.0).select_move(&board, Stone::Black, &mut rng),
+        None
+    );
+}
+
+#[test]
+fn light_playouts_end_with_two_passes() {
+    for policy in [PlayoutPolicy::light(), PlayoutPolicy::heavy()] {
+        let mut board = betago::Board::new(7);
+        let moves = playout(&mut board, Stone::Black, &policy, 1000, &mut seed::rng(9));
+        assert!(moves < 1000);
+        assert_eq!(
+            board.recent_moves()[..2],
+            [None, None],
+            "Passes are in the move history"
+        );
+    }
+}
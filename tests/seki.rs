@@ -0,0 +1,113 @@
+mod common;
+
+use betago::core::Rules;
+use betago::core::scoring::score;
+use betago::*;
+use common::{board_from_rows, pos};
+
+fn seki_with_eyes() -> Board {
+    board_from_rows(&[
+        ". B . W . W B",
+        "B B W W W W B",
+        "W W B B B B B",
+        "W W B . . . .",
+        "W W B . . . .",
+        "W W B . . . .",
+        ". W B . . . .",
+    ])
+}
+
+fn seki_without_eyes() -> Board {
+    board_from_rows(&[
+        "W B . W B . .",
+        "W B . W B . .",
+        "W B B W B . .",
+        "W W W B B . .",
+        ". . W B . . .",
+        ". . W B . . .",
+        ". . W B . . .",
+    ])
+}
+
+#[test]
+fn shared_liberty_between_one_eyed_groups() {
+    let board = seki_with_eyes();
+
+    assert_eq!(board.seki_liberties(), vec![pos(2, 0)]);
+    assert!(board.is_in_seki(pos(1, 0)), "Inner black group is in seki");
+    assert!(board.is_in_seki(pos(3, 0)), "Inner white group is in seki");
+    assert!(!board.is_in_seki(pos(6, 0)), "Outer wall is not in seki");
+}
+
+#[test]
+fn two_shared_liberties_without_eyes() {
+    let board = seki_without_eyes();
+
+    assert_eq!(board.seki_liberties(), vec![pos(2, 0), pos(2, 1)]);
+    assert_eq!(board.seki_groups().len(), 2);
+}
+
+#[test]
+fn capturing_race_is_not_seki() {
+    // Both sides fill (2, 0) only with self-atari, but each group has an
+    // outside liberty: whoever moves first wins the race.
+    let board = board_from_rows(&[
+        ". B . W . . .",
+        "W B B W B . .",
+        "W B B W B . .",
+        "W W W B B . .",
+        ". . W B . . .",
+        ". . W B . . .",
+        ". . W B . . .",
+    ]);
+
+    assert!(board.seki_liberties().is_empty());
+    assert!(!board.is_in_seki(pos(1, 0)));
+    assert!(!board.is_in_seki(pos(3, 0)));
+}
+
+#[test]
+fn dame_between_living_groups_is_not_seki() {
+    let board = board_from_rows(&[
+        ". B . W .",
+        "B B . W W",
+        ". B . W .",
+        "B B . W W",
+        ". B . W .",
+    ]);
+
+    assert!(board.seki_liberties().is_empty());
+    assert!(board.seki_groups().is_empty());
+}
+
+#[test]
+fn japanese_scoring_ignores_seki_eyes() {
+    let board = seki_with_eyes();
+    let result = score(&board, &[], (0, 0), Rules::Japanese, 0.0);
+
+    assert_eq!(result.black, 16.0);
+    assert_eq!(result.white, 1.0);
+    assert!(!result.black_territory.contains(&pos(0, 0)));
+    assert!(!result.white_territory.contains(&pos(4, 0)));
+}
+
+#[test]
+fn chinese_scoring_counts_seki_eyes() {
+    let board = seki_with_eyes();
+    let result = score(&board, &[], (0, 0), Rules::Chinese, 0.0);
+
+    assert_eq!(result.black, 31.0);
+    assert_eq!(result.white, 17.0);
+    assert!(!result.black_territory.contains(&pos(2, 0)));
+    assert!(!result.white_territory.contains(&pos(2, 0)));
+}
+
+#[test]
+fn seki_groups_are_never_dead() {
+    let board = seki_without_eyes();
+    let result = score(&board, &[pos(1, 0), pos(3, 0)], (0, 0), Rules::Japanese, 0.0);
+
+    assert!(result.dead.is_empty(), "Seki groups can't be marked dead");
+    assert_eq!(result.black, 17.0);
+    assert_eq!(result.white, 6.0);
+}
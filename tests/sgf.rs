@@ -0,0 +1,139 @@
+use betago::core::game::{Game, Move};
+use betago::sgf::{GameTree, SgfError};
+use betago::{Position, Stone};
+use rstest::rstest;
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+const WITH_VARIATIONS: &str = "(;GM[1]FF[4]SZ[9]KM[6.5]PB[Black]PW[White]
+    ;B[ee]C[Tengen]
+    ;W[ce]
+    (;B[gc];W[cc])
+    (;B[cg]C[Alternative \\] line]))";
+
+#[test]
+fn parses_header_and_variations() {
+    let tree = GameTree::parse(WITH_VARIATIONS).unwrap();
+
+    assert_eq!(tree.size, 9);
+    assert_eq!(tree.komi, 6.5);
+    assert_eq!(tree.black_player.as_deref(), Some("Black"));
+    assert_eq!(tree.white_player.as_deref(), Some("White"));
+
+    let main_line = tree.main_line();
+    assert_eq!(main_line.len(), 5);
+    assert_eq!(
+        tree.node(main_line[1]).mv,
+        Some(Move::Play(Stone::Black, pos(4, 4)))
+    );
+    assert_eq!(tree.node(main_line[1]).comment.as_deref(), Some("Tengen"));
+
+    let branch = tree.node(main_line[2]).children.clone();
+    assert_eq!(branch.len(), 2);
+    assert_eq!(tree.siblings(branch[1]), branch);
+    assert_eq!(
+        tree.node(branch[1]).comment.as_deref(),
+        Some("Alternative ] line")
+    );
+}
+
+#[test]
+fn round_trips_through_sgf() {
+    let tree = GameTree::parse(WITH_VARIATIONS).unwrap();
+    let reparsed = GameTree::parse(&tree.to_sgf()).unwrap();
+
+    assert_eq!(reparsed.len(), tree.len());
+    assert_eq!(reparsed.to_sgf(), tree.to_sgf());
+}
+
+#[test]
+fn state_tracks_captures_and_move_numbers() {
+    let tree = GameTree::parse("(;SZ[5];B[ba];W[aa];B[ab];W[];B[cc])").unwrap();
+    let line = tree.main_line();
+
+    let after_capture = tree.state_at(line[3]).unwrap();
+    assert_eq!(after_capture.captured, vec![pos(0, 0)]);
+    assert_eq!(after_capture.captures, (1, 0));
+    assert_eq!(after_capture.board.get_stone(pos(0, 0)).unwrap(), None);
+    assert_eq!(
+        after_capture.move_numbers[after_capture.board.pos_to_index(pos(0, 0))],
+        None
+    );
+    assert_eq!(
+        after_capture.move_numbers[after_capture.board.pos_to_index(pos(0, 1))],
+        Some(3)
+    );
+    assert_eq!(after_capture.to_play, Stone::White);
+
+    let last = tree.state_at(*line.last().unwrap()).unwrap();
+    assert_eq!(
+        last.move_numbers[last.board.pos_to_index(pos(2, 2))],
+        Some(5)
+    );
+    assert!(last.captured.is_empty());
+}
+
+#[test]
+fn add_child_reuses_existing_moves() {
+    let mut tree = GameTree::new(9);
+    let root = tree.root();
+
+    let first = tree.add_child(root, Move::Play(Stone::Black, pos(2, 2)));
+    let again = tree.add_child(root, Move::Play(Stone::Black, pos(2, 2)));
+    let other = tree.add_child(root, Move::Play(Stone::Black, pos(6, 6)));
+
+    assert_eq!(first, again);
+    assert_ne!(first, other);
+    assert_eq!(tree.node(root).children, vec![first, other]);
+}
+
+#[test]
+fn builds_tree_from_game_history() {
+    let mut game = Game::new(9);
+    game.make_move(pos(2, 2)).unwrap();
+    game.make_move(pos(6, 6)).unwrap();
+    game.pass();
+
+    let tree = GameTree::from_game(&game);
+    let moves: Vec<_> = tree
+        .main_line()
+        .into_iter()
+        .filter_map(|id| tree.node(id).mv)
+        .collect();
+
+    assert_eq!(moves, game.moves());
+    assert_eq!(tree.komi, game.komi());
+}
+
+#[rstest]
+#[case::out_of_range("(;SZ[9];B[jj])")]
+#[case::too_long("(;SZ[9];B[abc])")]
+#[case::not_a_letter("(;SZ[9];B[1a])")]
+fn rejects_invalid_coordinates(#[case] input: &str) {
+    assert!(matches!(
+        GameTree::parse(input),
+        Err(SgfError::InvalidCoordinate { .. })
+    ));
+}
+
+#[rstest]
+#[case::unclosed("(;SZ[9];B[aa]")]
+#[case::missing_value("(;SZ[9];B)")]
+fn rejects_malformed_input(#[case] input: &str) {
+    assert!(GameTree::parse(input).is_err());
+}
+
+#[rstest]
+#[case::listed_twice("(;SZ[5]AB[aa][aa])")]
+#[case::both_colours("(;SZ[5]AB[aa]AW[aa])")]
+#[case::on_a_played_stone("(;SZ[5];B[aa];AW[aa])")]
+fn rejects_setup_stones_on_taken_points(#[case] input: &str) {
+    let tree = GameTree::parse(input).unwrap();
+    let last = *tree.main_line().last().unwrap();
+    assert!(matches!(
+        tree.state_at(last),
+        Err(SgfError::IllegalSetup(_))
+    ));
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use betago::core::Symmetry;
+use betago::{Board, Stone};
+use common::{board_from_rows, pos};
+use rstest::rstest;
+
+fn sample() -> Board {
+    board_from_rows(&[
+        "B . . . .",
+        ". . W . .",
+        ". B . . .",
+        ". . . . W",
+        ". . . . .",
+    ])
+}
+
+#[rstest]
+#[case(Symmetry::Identity, 0, 1)]
+#[case(Symmetry::Rotate90, 3, 0)]
+#[case(Symmetry::Rotate180, 4, 3)]
+#[case(Symmetry::Rotate270, 1, 4)]
+#[case(Symmetry::FlipHorizontal, 4, 1)]
+#[case(Symmetry::FlipVertical, 0, 3)]
+#[case(Symmetry::Transpose, 1, 0)]
+#[case(Symmetry::AntiTranspose, 3, 4)]
+fn maps_points(#[case] symmetry: Symmetry, #[case] x: usize, #[case] y: usize) {
+    assert_eq!(pos(0, 1).transform(symmetry, 5), pos(x, y));
+}
+
+#[test]
+fn inverse_undoes_every_symmetry() {
+    for symmetry in Symmetry::ALL {
+        for size in [5, 6] {
+            for x in 0..size {
+                for y in 0..size {
+                    let p = pos(x, y).transform(symmetry, size);
+                    assert_eq!(p.transform(symmetry.inverse(), size), pos(x, y));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn transformed_boards_move_every_stone() {
+    let board = sample();
+    let hashes = board.symmetric_hashes();
+
+    for (symmetry, hash) in Symmetry::ALL.into_iter().zip(hashes) {
+        let transformed = board.transform(symmetry);
+        assert_eq!(transformed.hash(), hash);
+        for x in 0..5 {
+            for y in 0..5 {
+                assert_eq!(
+                    transformed
+                        .get_stone(pos(x, y).transform(symmetry, 5))
+                        .unwrap(),
+                    board.get_stone(pos(x, y)).unwrap()
+                );
+            }
+        }
+    }
+    assert_eq!(board.transform(Symmetry::Identity).hash(), board.hash());
+}
+
+#[test]
+fn symmetric_variants_share_the_canonical_hash() {
+    let board = sample();
+    let (canonical, symmetry) = board.canonical_hash();
+
+    assert_eq!(board.transform(symmetry).hash(), canonical);
+    for variant in Symmetry::ALL.map(|s| board.transform(s)) {
+        assert_eq!(variant.canonical_hash().0, canonical);
+    }
+
+    let mut other = sample();
+    other.place_stone(pos(4, 4), Stone::Black).unwrap();
+    assert_ne!(other.canonical_hash().0, canonical);
+}
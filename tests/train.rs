@@ -0,0 +1,219 @@
+use betago::ai::features::{self, PLANES};
+use betago::ai::train::{Sample, TrainError, TrainingExporter, read_shard, write_shard};
+use betago::sgf::GameTree;
+use std::path::{Path, PathBuf};
+
+const GAME: &str = "(;GM[1]SZ[9]KM[7]RE[W+2.5];B[ee];W[cc];B[gc];W[cg];B[ge];W[eg];B[];W[ec])";
+
+/// A fresh directory under the system temp dir, removed again on drop.
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("betago-train-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        ScratchDir(dir)
+    }
+}
+
+impl std::ops::Deref for ScratchDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The move of every sample must be a pass or a point the legal plane
+/// allows, in every orientation.
+fn assert_moves_are_legal(samples: &[Sample], size: usize) {
+    let area = size * size;
+    for sample in samples {
+        assert_eq!(sample.features.len(), PLANES * area);
+        assert!(sample.mv == area || sample.features[features::LEGAL * area + sample.mv] == 1.0);
+    }
+}
+
+#[test]
+fn samples_follow_the_game_from_the_side_to_move() {
+    let mut exporter = TrainingExporter::new(9, "never-written", 1);
+    exporter.augment = false;
+    let samples = exporter
+        .game_samples(&GameTree::parse(GAME).unwrap())
+        .unwrap();
+
+    assert_eq!(samples.len(), 8);
+    assert_eq!(samples[0].mv, 4 * 9 + 4);
+    assert_eq!(samples[6].mv, 81, "Black's pass");
+    let outcomes: Vec<f32> = samples.iter().map(|s| s.outcome).collect();
+    assert_eq!(outcomes, [-1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0]);
+    assert_moves_are_legal(&samples, 9);
+}
+
+#[test]
+fn augmentation_adds_every_orientation() {
+    let tree = GameTree::parse(GAME).unwrap();
+    let mut exporter = TrainingExporter::new(9, "never-written", 1);
+    let samples = exporter.game_samples(&tree).unwrap();
+    assert_eq!(samples.len(), 8 * 8);
+    assert_moves_are_legal(&samples, 9);
+
+    // The first move is on the centre point, which every symmetry keeps.
+    assert!(samples[..8].iter().all(|s| s.mv == 40));
+    let corners: Vec<usize> = samples[8..16].iter().map(|s| s.mv).collect();
+    for corner in [2 * 9 + 2, 2 * 9 + 6, 6 * 9 + 2, 6 * 9 + 6] {
+        assert_eq!(corners.iter().filter(|&&mv| mv == corner).count(), 2);
+    }
+
+    exporter.augment = false;
+    assert_eq!(exporter.game_samples(&tree).unwrap()[1], samples[8]);
+}
+
+#[test]
+fn handicap_games_start_with_white() {
+    let tree = GameTree::parse("(;GM[1]SZ[9]HA[2]AB[gc][cg]RE[B+R];W[ee];B[gg])").unwrap();
+    let mut exporter = TrainingExporter::new(9, "never-written", 1);
+    exporter.augment = false;
+    let samples = exporter.game_samples(&tree).unwrap();
+
+    assert_eq!(samples.len(), 2);
+    assert_eq!(samples[0].outcome, -1.0);
+    assert_eq!(
+        samples[0].features[features::OPPONENT_STONES * 81 + 2 * 9 + 6],
+        1.0
+    );
+}
+
+#[test]
+fn broken_records_are_rejected() {
+    let exporter = TrainingExporter::new(9, "never-written", 1);
+    let samples = |sgf: &str| exporter.game_samples(&GameTree::parse(sgf).unwrap());
+
+    assert!(matches!(
+        samples("(;SZ[9];B[ee];W[ee])"),
+        Err(TrainError::IllegalMove { number: 2, .. })
+    ));
+    assert!(matches!(
+        samples("(;SZ[9];B[ee];B[cc])"),
+        Err(TrainError::OutOfTurn { number: 2 })
+    ));
+    assert!(matches!(
+        samples("(;SZ[9];B[ee];W[cc]AB[gg])"),
+        Err(TrainError::Setup)
+    ));
+    assert!(matches!(
+        samples("(;SZ[13];B[ee])"),
+        Err(TrainError::BoardSize {
+            expected: 9,
+            found: 13
+        })
+    ));
+}
+
+#[test]
+fn directories_are_exported_to_shards() {
+    let games = ScratchDir::new("games");
+    std::fs::write(games.join("a.sgf"), GAME).unwrap();
+    std::fs::write(games.join("b.sgf"), "(;GM[1]SZ[9];B[cc];W[gg];B[cg])").unwrap();
+    std::fs::write(games.join("illegal.sgf"), "(;SZ[9];B[ee];W[ee])").unwrap();
+    std::fs::write(games.join("unreadable.sgf"), "(;SZ[9];B[ee").unwrap();
+    std::fs::write(games.join("notes.txt"), "not a game").unwrap();
+
+    let output = ScratchDir::new("shards");
+    let mut exporter = TrainingExporter::new(9, &*output, 7);
+    exporter.shard_size = 50;
+    assert_eq!(exporter.add_directory(&*games).unwrap(), (2, 2));
+    assert_eq!(exporter.samples(), (8 + 3) * 8);
+    assert_eq!(
+        exporter.shards().len(),
+        1,
+        "Full shards are written at once"
+    );
+
+    let shards = exporter.finish().unwrap();
+    assert_eq!(shards.len(), 2);
+    let (mut total, mut unknown) = (0, 0);
+    for shard in &shards {
+        let (size, samples) = read_shard(shard).unwrap();
+        assert_eq!(size, 9);
+        assert_moves_are_legal(&samples, 9);
+        total += samples.len();
+        unknown += samples.iter().filter(|s| s.outcome == 0.0).count();
+    }
+    assert_eq!(total, 88);
+    assert_eq!(unknown, 3 * 8, "The second game has no result");
+
+    std::fs::write(games.join("a.sgf"), "not a shard").unwrap();
+    assert!(matches!(
+        read_shard(games.join("a.sgf")),
+        Err(TrainError::BadMagic)
+    ));
+}
+
+#[test]
+fn shards_round_trip_samples() {
+    let output = ScratchDir::new("round-trip");
+    let mut exporter = TrainingExporter::new(9, &*output, 3);
+    exporter.augment = false;
+    let expected = exporter
+        .game_samples(&GameTree::parse(GAME).unwrap())
+        .unwrap();
+    exporter.add_game(&GameTree::parse(GAME).unwrap()).unwrap();
+
+    let shards = exporter.finish().unwrap();
+    let (_, mut samples) = read_shard(&shards[0]).unwrap();
+    samples.sort_by_key(|s| expected.iter().position(|e| e == s));
+    assert_eq!(samples, expected);
+}
+
+#[test]
+fn samples_of_another_size_are_not_written() {
+    let output = ScratchDir::new("wrong-size");
+    let sample = Sample {
+        features: vec![0.0; PLANES * 49],
+        mv: 49,
+        outcome: 0.0,
+    };
+    let path = output.join("shard.bin");
+    assert!(matches!(
+        write_shard(&path, 9, &[sample]),
+        Err(TrainError::SampleSize {
+            expected: 1863,
+            found: 1127
+        })
+    ));
+    assert!(!path.exists());
+}
+
+#[test]
+fn corrupt_headers_are_rejected() {
+    let output = ScratchDir::new("corrupt");
+    let header = |board_size: u32, count: u32| {
+        let mut bytes = b"BGTD".to_vec();
+        for field in [1, board_size, PLANES as u32, count] {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    };
+    let path = output.join("shard.bin");
+
+    std::fs::write(&path, header(u32::MAX, 1)).unwrap();
+    assert!(matches!(
+        read_shard(&path),
+        Err(TrainError::InvalidBoardSize(_))
+    ));
+    std::fs::write(&path, header(0, 1)).unwrap();
+    assert!(matches!(
+        read_shard(&path),
+        Err(TrainError::InvalidBoardSize(0))
+    ));
+    std::fs::write(&path, header(9, u32::MAX)).unwrap();
+    assert!(matches!(read_shard(&path), Err(TrainError::Io(_))));
+}
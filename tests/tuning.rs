@@ -0,0 +1,93 @@
+use betago::ai::heuristic::HeuristicAI;
+use betago::ai::playout::PlayoutPolicy;
+use betago::ai::random::RandomAI;
+use betago::ai::tuning::{MatchSettings, Spsa, win_rate};
+use betago::config::ConfigFile;
+use betago::core::seed;
+
+fn small_matches() -> MatchSettings {
+    MatchSettings {
+        board_size: 5,
+        komi: 0.5,
+        max_moves: 40,
+        ..MatchSettings::default()
+    }
+}
+
+#[test]
+fn weights_round_trip_through_config() {
+    let ai = HeuristicAI {
+        capture_weight: 12.0,
+        eye_weight: 0.25,
+        ..HeuristicAI::default()
+    };
+
+    let mut config = ConfigFile::default();
+    ai.write_config(&mut config, "white.");
+    let text = config.to_string();
+
+    let loaded = HeuristicAI::from_config(&ConfigFile::parse(&text).unwrap(), "white.").unwrap();
+    assert_eq!(loaded, ai);
+}
+
+#[test]
+fn pattern_weight_round_trips_through_a_file() {
+    let ai = HeuristicAI {
+        pattern_weight: 0.5,
+        ..HeuristicAI::default()
+    };
+    let path = std::env::temp_dir().join(format!("betago-weights-{}.conf", std::process::id()));
+    ai.save(&path).unwrap();
+    let loaded = HeuristicAI::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap(), ai);
+}
+
+#[test]
+fn missing_weights_keep_defaults() {
+    let config = ConfigFile::parse("save_weight = 4").unwrap();
+    let loaded = HeuristicAI::from_config(&config, "").unwrap();
+
+    assert_eq!(loaded.save_weight, 4.0);
+    assert_eq!(loaded.capture_weight, HeuristicAI::default().capture_weight);
+}
+
+#[test]
+fn win_rate_is_a_fraction_of_games() {
+    let rate = win_rate(
+        &mut HeuristicAI::default(),
+        &mut RandomAI::new(PlayoutPolicy::light(), 1),
+        10,
+        &MatchSettings {
+            opening_moves: 0,
+            ..small_matches()
+        },
+        &mut seed::rng(42),
+    );
+    assert!((0.0..=1.0).contains(&rate));
+}
+
+#[test]
+fn spsa_runs_and_keeps_weights_non_negative() {
+    let spsa = Spsa {
+        iterations: 2,
+        games_per_side: 2,
+        evaluation_games: 2,
+        matches: small_matches(),
+        ..Spsa::default()
+    };
+
+    let mut steps = 0;
+    let result = spsa.tune(
+        HeuristicAI::default(),
+        &mut HeuristicAI::default(),
+        &mut seed::rng(42),
+        |_, _, _| steps += 1,
+    );
+
+    assert_eq!(steps, 2);
+    assert_eq!(result.history.len(), 3);
+    assert!(result.best.weights().iter().all(|&w| w >= 0.0));
+    assert!(result.history.iter().all(|&r| r <= result.best_win_rate));
+}